@@ -0,0 +1,27 @@
+CREATE TABLE IF NOT EXISTS sync_runs (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    token_fingerprint VARCHAR(64) NOT NULL,
+    started_at DATETIME NOT NULL,
+    finished_at DATETIME,
+    accounts_touched BIGINT NOT NULL DEFAULT 0,
+    windows_fetched BIGINT NOT NULL DEFAULT 0,
+    items_fetched BIGINT NOT NULL DEFAULT 0,
+    items_inserted BIGINT NOT NULL DEFAULT 0,
+    errors TEXT
+);
+
+CREATE TABLE IF NOT EXISTS sync_windows (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    run_id BIGINT NOT NULL,
+    account_id VARCHAR(255) NOT NULL,
+    window_start DATETIME NOT NULL,
+    window_end DATETIME,
+    status VARCHAR(16) NOT NULL,
+    items_fetched BIGINT NOT NULL DEFAULT 0,
+    items_inserted BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    fetched_at DATETIME NOT NULL,
+    FOREIGN KEY (run_id) REFERENCES sync_runs(id),
+    FOREIGN KEY (account_id) REFERENCES accounts(id),
+    INDEX sync_windows_account_id_window_start_idx (account_id, window_start)
+);
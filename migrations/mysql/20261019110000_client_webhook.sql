@@ -0,0 +1 @@
+ALTER TABLE client_info ADD COLUMN webhook_url TEXT;
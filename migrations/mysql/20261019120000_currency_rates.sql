@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS currency_rates (
+    id BIGINT AUTO_INCREMENT PRIMARY KEY,
+    currency_code_a INTEGER NOT NULL,
+    currency_code_b INTEGER NOT NULL,
+    date DATETIME NOT NULL,
+    rate_buy DOUBLE,
+    rate_sell DOUBLE,
+    rate_cross DOUBLE,
+    fetched_at DATETIME NOT NULL,
+    UNIQUE (currency_code_a, currency_code_b, date)
+);
@@ -0,0 +1,310 @@
+-- Generated by scripts/mcc_codes.py from data/mcc_codes.csv, edit those instead.
+CREATE TABLE IF NOT EXISTS mcc_codes (
+    code INTEGER PRIMARY KEY,
+    description VARCHAR(255) NOT NULL,
+    iso_group VARCHAR(255) NOT NULL
+);
+
+INSERT INTO mcc_codes (code, description, iso_group) VALUES
+    (742, 'Veterinary Services', 'Agricultural Services'),
+    (763, 'Agricultural Cooperatives', 'Agricultural Services'),
+    (780, 'Landscaping and Horticultural Services', 'Agricultural Services'),
+    (1520, 'General Contractors - Residential and Commercial', 'Contracted Services'),
+    (1711, 'Heating, Plumbing, and Air Conditioning Contractors', 'Contracted Services'),
+    (1731, 'Electrical Contractors', 'Contracted Services'),
+    (1740, 'Masonry, Stonework, Tile Setting, Plastering and Insulation Contractors', 'Contracted Services'),
+    (1750, 'Carpentry Contractors', 'Contracted Services'),
+    (1761, 'Roofing, Siding, and Sheet Metal Work Contractors', 'Contracted Services'),
+    (1771, 'Concrete Work Contractors', 'Contracted Services'),
+    (1799, 'Special Trade Contractors', 'Contracted Services'),
+    (2741, 'Miscellaneous Publishing and Printing', 'Contracted Services'),
+    (2791, 'Typesetting, Platemaking, and Related Services', 'Contracted Services'),
+    (2842, 'Specialty Cleaning, Polishing, and Sanitation Preparations', 'Contracted Services'),
+    (3000, 'Airlines', 'Airlines'),
+    (3351, 'Car Rental Agencies', 'Car Rental'),
+    (3501, 'Hotels, Motels, Resorts', 'Lodging'),
+    (4011, 'Railroads', 'Transportation Services'),
+    (4111, 'Local and Suburban Commuter Passenger Transportation', 'Transportation Services'),
+    (4112, 'Passenger Railways', 'Transportation Services'),
+    (4119, 'Ambulance Services', 'Transportation Services'),
+    (4121, 'Taxicabs and Limousines', 'Transportation Services'),
+    (4131, 'Bus Lines', 'Transportation Services'),
+    (4214, 'Motor Freight Carriers and Trucking', 'Transportation Services'),
+    (4215, 'Courier Services', 'Transportation Services'),
+    (4225, 'Public Warehousing and Storage', 'Transportation Services'),
+    (4411, 'Steamship and Cruise Lines', 'Transportation Services'),
+    (4457, 'Boat Rentals and Leasing', 'Transportation Services'),
+    (4468, 'Marinas, Marine Service, and Supplies', 'Transportation Services'),
+    (4511, 'Airlines and Air Carriers', 'Airlines'),
+    (4582, 'Airports, Flying Fields, and Airport Terminals', 'Transportation Services'),
+    (4722, 'Travel Agencies and Tour Operators', 'Transportation Services'),
+    (4784, 'Tolls and Bridge Fees', 'Transportation Services'),
+    (4789, 'Transportation Services', 'Transportation Services'),
+    (4812, 'Telecommunication Equipment and Telephone Sales', 'Telecommunication Services'),
+    (4814, 'Telecommunication Services', 'Telecommunication Services'),
+    (4816, 'Computer Network and Information Services', 'Telecommunication Services'),
+    (4821, 'Telegraph Services', 'Telecommunication Services'),
+    (4829, 'Wire Transfers and Money Orders', 'Financial Services'),
+    (4899, 'Cable, Satellite, and Other Pay Television and Radio Services', 'Utility Services'),
+    (4900, 'Utilities - Electric, Gas, Water, and Sanitary', 'Utility Services'),
+    (5013, 'Motor Vehicle Supplies and New Parts', 'Wholesale Distributors and Manufacturers'),
+    (5021, 'Office and Commercial Furniture', 'Wholesale Distributors and Manufacturers'),
+    (5039, 'Construction Materials', 'Wholesale Distributors and Manufacturers'),
+    (5044, 'Photographic, Photocopy, Microfilm Equipment and Supplies', 'Wholesale Distributors and Manufacturers'),
+    (5045, 'Computers, Computer Peripheral Equipment, and Software', 'Wholesale Distributors and Manufacturers'),
+    (5046, 'Commercial Equipment', 'Wholesale Distributors and Manufacturers'),
+    (5047, 'Medical, Dental, Ophthalmic, and Hospital Equipment and Supplies', 'Wholesale Distributors and Manufacturers'),
+    (5051, 'Metal Service Centers and Offices', 'Wholesale Distributors and Manufacturers'),
+    (5065, 'Electrical Parts and Equipment', 'Wholesale Distributors and Manufacturers'),
+    (5072, 'Hardware, Equipment, and Supplies', 'Wholesale Distributors and Manufacturers'),
+    (5074, 'Plumbing and Heating Equipment and Supplies', 'Wholesale Distributors and Manufacturers'),
+    (5085, 'Industrial Supplies', 'Wholesale Distributors and Manufacturers'),
+    (5094, 'Precious Stones and Metals, Watches and Jewelry', 'Wholesale Distributors and Manufacturers'),
+    (5099, 'Durable Goods', 'Wholesale Distributors and Manufacturers'),
+    (5111, 'Stationery, Office Supplies, Printing and Writing Paper', 'Wholesale Distributors and Manufacturers'),
+    (5122, 'Drugs, Drug Proprietaries, and Druggist Sundries', 'Wholesale Distributors and Manufacturers'),
+    (5131, 'Piece Goods, Notions, and Other Dry Goods', 'Wholesale Distributors and Manufacturers'),
+    (5137, 'Uniforms and Commercial Clothing', 'Wholesale Distributors and Manufacturers'),
+    (5139, 'Commercial Footwear', 'Wholesale Distributors and Manufacturers'),
+    (5169, 'Chemicals and Allied Products', 'Wholesale Distributors and Manufacturers'),
+    (5172, 'Petroleum and Petroleum Products', 'Wholesale Distributors and Manufacturers'),
+    (5192, 'Books, Periodicals, and Newspapers', 'Wholesale Distributors and Manufacturers'),
+    (5193, 'Florists Supplies, Nursery Stock, and Flowers', 'Wholesale Distributors and Manufacturers'),
+    (5198, 'Paints, Varnishes, and Supplies', 'Wholesale Distributors and Manufacturers'),
+    (5199, 'Nondurable Goods', 'Wholesale Distributors and Manufacturers'),
+    (5200, 'Home Supply Warehouse Stores', 'Retail Outlet Services'),
+    (5211, 'Lumber and Building Materials Stores', 'Retail Outlet Services'),
+    (5231, 'Glass, Paint, and Wallpaper Stores', 'Retail Outlet Services'),
+    (5251, 'Hardware Stores', 'Retail Outlet Services'),
+    (5261, 'Nurseries and Lawn and Garden Supply Stores', 'Retail Outlet Services'),
+    (5271, 'Mobile Home Dealers', 'Retail Outlet Services'),
+    (5300, 'Wholesale Clubs', 'Retail Outlet Services'),
+    (5309, 'Duty Free Stores', 'Retail Outlet Services'),
+    (5310, 'Discount Stores', 'Retail Outlet Services'),
+    (5311, 'Department Stores', 'Retail Outlet Services'),
+    (5331, 'Variety Stores', 'Retail Outlet Services'),
+    (5399, 'Miscellaneous General Merchandise', 'Retail Outlet Services'),
+    (5411, 'Grocery Stores and Supermarkets', 'Food Stores'),
+    (5422, 'Freezer and Locker Meat Provisioners', 'Food Stores'),
+    (5441, 'Candy, Nut, and Confectionery Stores', 'Food Stores'),
+    (5451, 'Dairy Products Stores', 'Food Stores'),
+    (5462, 'Bakeries', 'Food Stores'),
+    (5499, 'Miscellaneous Food Stores - Convenience Stores and Specialty Markets', 'Food Stores'),
+    (5511, 'Car and Truck Dealers (New and Used)', 'Automobiles and Vehicles'),
+    (5521, 'Car and Truck Dealers (Used Only)', 'Automobiles and Vehicles'),
+    (5531, 'Auto and Home Supply Stores', 'Automobiles and Vehicles'),
+    (5532, 'Automotive Tire Stores', 'Automobiles and Vehicles'),
+    (5533, 'Automotive Parts and Accessories Stores', 'Automobiles and Vehicles'),
+    (5541, 'Service Stations', 'Automobiles and Vehicles'),
+    (5542, 'Automated Fuel Dispensers', 'Automobiles and Vehicles'),
+    (5551, 'Boat Dealers', 'Automobiles and Vehicles'),
+    (5561, 'Camper, Recreational and Utility Trailer Dealers', 'Automobiles and Vehicles'),
+    (5571, 'Motorcycle Shops and Dealers', 'Automobiles and Vehicles'),
+    (5592, 'Motor Homes Dealers', 'Automobiles and Vehicles'),
+    (5598, 'Snowmobile Dealers', 'Automobiles and Vehicles'),
+    (5599, 'Miscellaneous Automotive, Aircraft, and Farm Equipment Dealers', 'Automobiles and Vehicles'),
+    (5611, 'Men''s and Boys'' Clothing and Accessories Stores', 'Clothing Stores'),
+    (5621, 'Women''s Ready-to-Wear Stores', 'Clothing Stores'),
+    (5631, 'Women''s Accessory and Specialty Shops', 'Clothing Stores'),
+    (5641, 'Children''s and Infants'' Wear Stores', 'Clothing Stores'),
+    (5651, 'Family Clothing Stores', 'Clothing Stores'),
+    (5655, 'Sports and Riding Apparel Stores', 'Clothing Stores'),
+    (5661, 'Shoe Stores', 'Clothing Stores'),
+    (5681, 'Furriers and Fur Shops', 'Clothing Stores'),
+    (5691, 'Men''s and Women''s Clothing Stores', 'Clothing Stores'),
+    (5697, 'Tailors, Seamstresses, Mending, and Alterations', 'Clothing Stores'),
+    (5698, 'Wig and Toupee Stores', 'Clothing Stores'),
+    (5699, 'Miscellaneous Apparel and Accessory Shops', 'Clothing Stores'),
+    (5712, 'Furniture, Home Furnishings, and Equipment Stores', 'Home Furnishing and Electronics Stores'),
+    (5713, 'Floor Covering Stores', 'Home Furnishing and Electronics Stores'),
+    (5714, 'Drapery, Window Covering, and Upholstery Stores', 'Home Furnishing and Electronics Stores'),
+    (5718, 'Fireplaces, Fireplace Screens, and Accessories Stores', 'Home Furnishing and Electronics Stores'),
+    (5719, 'Miscellaneous Home Furnishing Specialty Stores', 'Home Furnishing and Electronics Stores'),
+    (5722, 'Household Appliance Stores', 'Home Furnishing and Electronics Stores'),
+    (5732, 'Electronics Stores', 'Home Furnishing and Electronics Stores'),
+    (5733, 'Music Stores - Musical Instruments, Pianos, and Sheet Music', 'Home Furnishing and Electronics Stores'),
+    (5734, 'Computer Software Stores', 'Home Furnishing and Electronics Stores'),
+    (5735, 'Record Stores', 'Home Furnishing and Electronics Stores'),
+    (5811, 'Caterers', 'Eating and Drinking Places'),
+    (5812, 'Eating Places and Restaurants', 'Eating and Drinking Places'),
+    (5813, 'Drinking Places - Bars, Taverns, Nightclubs', 'Eating and Drinking Places'),
+    (5814, 'Fast Food Restaurants', 'Eating and Drinking Places'),
+    (5815, 'Digital Goods - Media, Books, Movies, Music', 'Digital Goods'),
+    (5816, 'Digital Goods - Games', 'Digital Goods'),
+    (5817, 'Digital Goods - Applications', 'Digital Goods'),
+    (5818, 'Digital Goods - Large Digital Goods Merchant', 'Digital Goods'),
+    (5912, 'Drug Stores and Pharmacies', 'Miscellaneous Stores'),
+    (5921, 'Package Stores - Beer, Wine, and Liquor', 'Miscellaneous Stores'),
+    (5931, 'Used Merchandise and Secondhand Stores', 'Miscellaneous Stores'),
+    (5932, 'Antique Shops', 'Miscellaneous Stores'),
+    (5933, 'Pawn Shops', 'Miscellaneous Stores'),
+    (5935, 'Wrecking and Salvage Yards', 'Miscellaneous Stores'),
+    (5937, 'Antique Reproductions', 'Miscellaneous Stores'),
+    (5940, 'Bicycle Shops', 'Miscellaneous Stores'),
+    (5941, 'Sporting Goods Stores', 'Miscellaneous Stores'),
+    (5942, 'Book Stores', 'Miscellaneous Stores'),
+    (5943, 'Stationery, Office, and School Supply Stores', 'Miscellaneous Stores'),
+    (5944, 'Jewelry, Watch, Clock, and Silverware Stores', 'Miscellaneous Stores'),
+    (5945, 'Hobby, Toy, and Game Shops', 'Miscellaneous Stores'),
+    (5946, 'Camera and Photographic Supply Stores', 'Miscellaneous Stores'),
+    (5947, 'Gift, Card, Novelty, and Souvenir Shops', 'Miscellaneous Stores'),
+    (5948, 'Luggage and Leather Goods Stores', 'Miscellaneous Stores'),
+    (5949, 'Sewing, Needlework, Fabric, and Piece Goods Stores', 'Miscellaneous Stores'),
+    (5950, 'Glassware and Crystal Stores', 'Miscellaneous Stores'),
+    (5960, 'Direct Marketing - Insurance Services', 'Direct Marketing'),
+    (5961, 'Mail Order Houses', 'Direct Marketing'),
+    (5962, 'Direct Marketing - Travel-Related Arrangement Services', 'Direct Marketing'),
+    (5963, 'Door-to-Door Sales', 'Direct Marketing'),
+    (5964, 'Direct Marketing - Catalog Merchant', 'Direct Marketing'),
+    (5965, 'Direct Marketing - Combination Catalog and Retail Merchant', 'Direct Marketing'),
+    (5966, 'Direct Marketing - Outbound Telemarketing Merchant', 'Direct Marketing'),
+    (5967, 'Direct Marketing - Inbound Teleservices Merchant', 'Direct Marketing'),
+    (5968, 'Direct Marketing - Continuity/Subscription Merchant', 'Direct Marketing'),
+    (5969, 'Direct Marketing - Other Direct Marketers', 'Direct Marketing'),
+    (5970, 'Artist''s Supply and Craft Shops', 'Miscellaneous Stores'),
+    (5971, 'Art Dealers and Galleries', 'Miscellaneous Stores'),
+    (5972, 'Stamp and Coin Stores', 'Miscellaneous Stores'),
+    (5973, 'Religious Goods Stores', 'Miscellaneous Stores'),
+    (5975, 'Hearing Aids - Sales, Service, and Supplies', 'Miscellaneous Stores'),
+    (5976, 'Orthopedic Goods and Prosthetic Devices', 'Miscellaneous Stores'),
+    (5977, 'Cosmetic Stores', 'Miscellaneous Stores'),
+    (5978, 'Typewriter Stores', 'Miscellaneous Stores'),
+    (5983, 'Fuel Dealers - Fuel Oil, Wood, Coal, and Liquefied Petroleum', 'Miscellaneous Stores'),
+    (5992, 'Florists', 'Miscellaneous Stores'),
+    (5993, 'Cigar Stores and Stands', 'Miscellaneous Stores'),
+    (5994, 'News Dealers and Newsstands', 'Miscellaneous Stores'),
+    (5995, 'Pet Shops, Pet Food, and Supplies', 'Miscellaneous Stores'),
+    (5996, 'Swimming Pools - Sales and Supplies', 'Miscellaneous Stores'),
+    (5997, 'Electric Razor Stores - Sales and Service', 'Miscellaneous Stores'),
+    (5998, 'Tent and Awning Shops', 'Miscellaneous Stores'),
+    (5999, 'Miscellaneous and Specialty Retail Stores', 'Miscellaneous Stores'),
+    (6010, 'Financial Institutions - Manual Cash Disbursements', 'Financial Services'),
+    (6011, 'Financial Institutions - Automated Cash Disbursements', 'Financial Services'),
+    (6012, 'Financial Institutions - Merchandise and Services', 'Financial Services'),
+    (6050, 'Quasi Cash - Member Financial Institution', 'Financial Services'),
+    (6051, 'Non-Financial Institutions - Foreign Currency, Money Orders, Travelers'' Cheques', 'Financial Services'),
+    (6211, 'Security Brokers and Dealers', 'Financial Services'),
+    (6300, 'Insurance Sales, Underwriting, and Premiums', 'Financial Services'),
+    (6513, 'Real Estate Agents and Managers - Rentals', 'Financial Services'),
+    (6529, 'Remote Stored Value Load - Member Financial Institution', 'Financial Services'),
+    (6530, 'Remote Stored Value Load - Merchant', 'Financial Services'),
+    (6532, 'Payment Transaction - Member Financial Institution', 'Financial Services'),
+    (6533, 'Payment Transaction - Merchant', 'Financial Services'),
+    (6536, 'MoneySend Intracountry', 'Financial Services'),
+    (6537, 'MoneySend Intercountry', 'Financial Services'),
+    (6538, 'MoneySend Funding', 'Financial Services'),
+    (6540, 'Non-Financial Institutions - Stored Value Card Purchase/Load', 'Financial Services'),
+    (7011, 'Lodging - Hotels, Motels, and Resorts', 'Lodging'),
+    (7012, 'Timeshares', 'Lodging'),
+    (7032, 'Sporting and Recreational Camps', 'Lodging'),
+    (7033, 'Trailer Parks and Campgrounds', 'Lodging'),
+    (7210, 'Laundry, Cleaning, and Garment Services', 'Personal Service Providers'),
+    (7211, 'Laundries - Family and Commercial', 'Personal Service Providers'),
+    (7216, 'Dry Cleaners', 'Personal Service Providers'),
+    (7217, 'Carpet and Upholstery Cleaning', 'Personal Service Providers'),
+    (7221, 'Photographic Studios', 'Personal Service Providers'),
+    (7230, 'Beauty and Barber Shops', 'Personal Service Providers'),
+    (7251, 'Shoe Repair Shops, Shoe Shine Parlors, and Hat Cleaning Shops', 'Personal Service Providers'),
+    (7261, 'Funeral Services and Crematories', 'Personal Service Providers'),
+    (7273, 'Dating and Escort Services', 'Personal Service Providers'),
+    (7276, 'Tax Preparation Services', 'Personal Service Providers'),
+    (7277, 'Counseling Services - Debt, Marriage, and Personal', 'Personal Service Providers'),
+    (7278, 'Buying and Shopping Services and Clubs', 'Personal Service Providers'),
+    (7296, 'Clothing Rental - Costumes, Uniforms, and Formal Wear', 'Personal Service Providers'),
+    (7297, 'Massage Parlors', 'Personal Service Providers'),
+    (7298, 'Health and Beauty Spas', 'Personal Service Providers'),
+    (7299, 'Miscellaneous Personal Services', 'Personal Service Providers'),
+    (7311, 'Advertising Services', 'Business Services'),
+    (7321, 'Consumer Credit Reporting Agencies', 'Business Services'),
+    (7333, 'Commercial Photography, Art, and Graphics', 'Business Services'),
+    (7338, 'Quick Copy, Reproduction, and Blueprinting Services', 'Business Services'),
+    (7339, 'Stenographic and Secretarial Support Services', 'Business Services'),
+    (7342, 'Exterminating and Disinfecting Services', 'Business Services'),
+    (7349, 'Cleaning, Maintenance, and Janitorial Services', 'Business Services'),
+    (7361, 'Employment Agencies and Temporary Help Services', 'Business Services'),
+    (7372, 'Computer Programming, Data Processing, and Integrated Systems Design Services', 'Business Services'),
+    (7375, 'Information Retrieval Services', 'Business Services'),
+    (7379, 'Computer Maintenance, Repair and Services', 'Business Services'),
+    (7392, 'Management, Consulting, and Public Relations Services', 'Business Services'),
+    (7393, 'Detective Agencies, Protective Agencies, and Security Services', 'Business Services'),
+    (7394, 'Equipment, Tool, Furniture, and Appliance Rental and Leasing', 'Business Services'),
+    (7395, 'Photofinishing Laboratories and Photo Developing', 'Business Services'),
+    (7399, 'Business Services', 'Business Services'),
+    (7512, 'Automobile Rental Agency', 'Car Rental'),
+    (7513, 'Truck and Utility Trailer Rentals', 'Car Rental'),
+    (7519, 'Motor Home and Recreational Vehicle Rentals', 'Car Rental'),
+    (7523, 'Parking Lots, Parking Meters and Garages', 'Automotive Services'),
+    (7531, 'Automotive Body Repair Shops', 'Automotive Services'),
+    (7534, 'Tire Retreading and Repair Shops', 'Automotive Services'),
+    (7535, 'Automotive Paint Shops', 'Automotive Services'),
+    (7538, 'Automotive Service Shops', 'Automotive Services'),
+    (7542, 'Car Washes', 'Automotive Services'),
+    (7549, 'Towing Services', 'Automotive Services'),
+    (7622, 'Electronics Repair Shops', 'Repair Services'),
+    (7623, 'Air Conditioning and Refrigeration Repair Shops', 'Repair Services'),
+    (7629, 'Electrical and Small Appliance Repair Shops', 'Repair Services'),
+    (7631, 'Watch, Clock, and Jewelry Repair Shops', 'Repair Services'),
+    (7641, 'Furniture - Reupholstery, Repair, and Refinishing', 'Repair Services'),
+    (7692, 'Welding Services', 'Repair Services'),
+    (7699, 'Miscellaneous Repair Shops and Related Services', 'Repair Services'),
+    (7800, 'Government-Owned Lotteries', 'Amusement and Entertainment'),
+    (7801, 'Government Licensed On-Line Casinos', 'Amusement and Entertainment'),
+    (7802, 'Government-Licensed Horse/Dog Racing', 'Amusement and Entertainment'),
+    (7829, 'Motion Picture and Video Tape Production and Distribution', 'Amusement and Entertainment'),
+    (7832, 'Motion Picture Theaters', 'Amusement and Entertainment'),
+    (7841, 'Video Tape Rental Stores', 'Amusement and Entertainment'),
+    (7911, 'Dance Halls, Studios, and Schools', 'Amusement and Entertainment'),
+    (7922, 'Theatrical Producers and Ticket Agencies', 'Amusement and Entertainment'),
+    (7929, 'Bands, Orchestras, and Miscellaneous Entertainers', 'Amusement and Entertainment'),
+    (7932, 'Billiard and Pool Establishments', 'Amusement and Entertainment'),
+    (7933, 'Bowling Alleys', 'Amusement and Entertainment'),
+    (7941, 'Commercial Sports, Professional Sports Clubs, and Athletic Fields', 'Amusement and Entertainment'),
+    (7991, 'Tourist Attractions and Exhibits', 'Amusement and Entertainment'),
+    (7992, 'Public Golf Courses', 'Amusement and Entertainment'),
+    (7993, 'Video Amusement Game Supplies', 'Amusement and Entertainment'),
+    (7994, 'Video Game Arcades and Establishments', 'Amusement and Entertainment'),
+    (7995, 'Betting, Including Lottery Tickets, Casino Gaming Chips, and Wagers', 'Amusement and Entertainment'),
+    (7996, 'Amusement Parks, Circuses, Carnivals, and Fortune Tellers', 'Amusement and Entertainment'),
+    (7997, 'Membership Clubs - Sports, Recreation, Athletic; Country Clubs', 'Amusement and Entertainment'),
+    (7998, 'Aquariums, Seaquariums, and Dolphinariums', 'Amusement and Entertainment'),
+    (7999, 'Recreation Services', 'Amusement and Entertainment'),
+    (8011, 'Doctors and Physicians', 'Medical Services'),
+    (8021, 'Dentists and Orthodontists', 'Medical Services'),
+    (8031, 'Osteopaths', 'Medical Services'),
+    (8041, 'Chiropractors', 'Medical Services'),
+    (8042, 'Optometrists and Ophthalmologists', 'Medical Services'),
+    (8043, 'Opticians, Optical Goods, and Eyeglasses', 'Medical Services'),
+    (8049, 'Podiatrists and Chiropodists', 'Medical Services'),
+    (8050, 'Nursing and Personal Care Facilities', 'Medical Services'),
+    (8062, 'Hospitals', 'Medical Services'),
+    (8071, 'Medical and Dental Laboratories', 'Medical Services'),
+    (8099, 'Medical Services and Health Practitioners', 'Medical Services'),
+    (8111, 'Legal Services and Attorneys', 'Professional Services'),
+    (8211, 'Elementary and Secondary Schools', 'Educational Services'),
+    (8220, 'Colleges, Universities, Professional Schools, and Junior Colleges', 'Educational Services'),
+    (8241, 'Correspondence Schools', 'Educational Services'),
+    (8244, 'Business and Secretarial Schools', 'Educational Services'),
+    (8249, 'Trade and Vocational Schools', 'Educational Services'),
+    (8299, 'Schools and Educational Services', 'Educational Services'),
+    (8351, 'Child Care Services', 'Social Services'),
+    (8398, 'Charitable and Social Service Organizations', 'Social Services'),
+    (8641, 'Civic, Social, and Fraternal Associations', 'Membership Organizations'),
+    (8651, 'Political Organizations', 'Membership Organizations'),
+    (8661, 'Religious Organizations', 'Membership Organizations'),
+    (8675, 'Automobile Associations', 'Membership Organizations'),
+    (8699, 'Membership Organizations', 'Membership Organizations'),
+    (8734, 'Testing Laboratories', 'Professional Services'),
+    (8911, 'Architectural, Engineering, and Surveying Services', 'Professional Services'),
+    (8931, 'Accounting, Auditing, and Bookkeeping Services', 'Professional Services'),
+    (8999, 'Professional Services', 'Professional Services'),
+    (9211, 'Court Costs, Including Alimony and Child Support', 'Government Services'),
+    (9222, 'Fines', 'Government Services'),
+    (9223, 'Bail and Bond Payments', 'Government Services'),
+    (9311, 'Tax Payments', 'Government Services'),
+    (9399, 'Government Services', 'Government Services'),
+    (9402, 'Postal Services - Government Only', 'Government Services'),
+    (9405, 'Intra-Government Purchases - Government Only', 'Government Services'),
+    (9950, 'Intra-Company Purchases', 'Intra-Company Purchases')
+ON DUPLICATE KEY UPDATE description = VALUES(description), iso_group = VALUES(iso_group);
+
+ALTER TABLE statement_items ADD COLUMN category VARCHAR(255);
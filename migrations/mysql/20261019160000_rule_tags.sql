@@ -0,0 +1,7 @@
+CREATE TABLE IF NOT EXISTS statement_item_tags (
+    statement_item_id VARCHAR(255) NOT NULL,
+    tag VARCHAR(255) NOT NULL,
+    PRIMARY KEY (statement_item_id, tag),
+    FOREIGN KEY (statement_item_id) REFERENCES statement_items(id) ON DELETE CASCADE,
+    INDEX statement_item_tags_tag_idx (tag)
+);
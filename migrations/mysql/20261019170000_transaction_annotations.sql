@@ -0,0 +1,14 @@
+-- No foreign keys on purpose: annotations outlive deleted and re-fetched statement items.
+CREATE TABLE IF NOT EXISTS transaction_annotations (
+    statement_item_id VARCHAR(255) PRIMARY KEY,
+    note TEXT NOT NULL,
+    updated_at DATETIME NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS transaction_tags (
+    statement_item_id VARCHAR(255) NOT NULL,
+    tag VARCHAR(255) NOT NULL,
+    created_at DATETIME NOT NULL,
+    PRIMARY KEY (statement_item_id, tag),
+    INDEX transaction_tags_tag_idx (tag)
+);
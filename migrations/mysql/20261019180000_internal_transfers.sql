@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS internal_transfers (
+    outflow_item_id VARCHAR(255) PRIMARY KEY,
+    inflow_item_id VARCHAR(255) NOT NULL UNIQUE,
+    matched_by VARCHAR(32) NOT NULL,
+    matched_at DATETIME NOT NULL,
+    FOREIGN KEY (outflow_item_id) REFERENCES statement_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (inflow_item_id) REFERENCES statement_items(id) ON DELETE CASCADE
+);
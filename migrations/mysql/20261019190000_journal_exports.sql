@@ -0,0 +1,8 @@
+-- Items already written to each journal, so incremental exports only append new ones.
+CREATE TABLE IF NOT EXISTS journal_exports (
+    format VARCHAR(32) NOT NULL,
+    target VARCHAR(255) NOT NULL,
+    statement_item_id VARCHAR(255) NOT NULL,
+    exported_at DATETIME NOT NULL,
+    PRIMARY KEY (format, target, statement_item_id)
+);
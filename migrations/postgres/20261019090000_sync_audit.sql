@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS sync_runs (
+    id BIGSERIAL PRIMARY KEY,
+    token_fingerprint TEXT NOT NULL,
+    started_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+    finished_at TIMESTAMP WITHOUT TIME ZONE,
+    accounts_touched BIGINT NOT NULL DEFAULT 0,
+    windows_fetched BIGINT NOT NULL DEFAULT 0,
+    items_fetched BIGINT NOT NULL DEFAULT 0,
+    items_inserted BIGINT NOT NULL DEFAULT 0,
+    errors TEXT
+);
+
+CREATE TABLE IF NOT EXISTS sync_windows (
+    id BIGSERIAL PRIMARY KEY,
+    run_id BIGINT NOT NULL,
+    account_id TEXT NOT NULL,
+    window_start TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+    window_end TIMESTAMP WITHOUT TIME ZONE,
+    status TEXT NOT NULL,
+    items_fetched BIGINT NOT NULL DEFAULT 0,
+    items_inserted BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    fetched_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+    FOREIGN KEY (run_id) REFERENCES sync_runs(id),
+    FOREIGN KEY (account_id) REFERENCES accounts(id)
+);
+
+CREATE INDEX IF NOT EXISTS sync_windows_account_id_window_start_idx ON sync_windows (account_id, window_start);
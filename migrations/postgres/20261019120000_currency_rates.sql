@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS currency_rates (
+    id BIGSERIAL PRIMARY KEY,
+    currency_code_a INTEGER NOT NULL,
+    currency_code_b INTEGER NOT NULL,
+    date TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+    rate_buy DOUBLE PRECISION,
+    rate_sell DOUBLE PRECISION,
+    rate_cross DOUBLE PRECISION,
+    fetched_at TIMESTAMP WITHOUT TIME ZONE NOT NULL,
+    UNIQUE (currency_code_a, currency_code_b, date)
+);
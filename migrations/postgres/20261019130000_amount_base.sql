@@ -0,0 +1,2 @@
+ALTER TABLE statement_items ADD COLUMN amount_base BIGINT;
+ALTER TABLE statement_items ADD COLUMN amount_base_currency INTEGER;
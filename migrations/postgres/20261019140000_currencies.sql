@@ -0,0 +1,163 @@
+CREATE TABLE IF NOT EXISTS currencies (
+    numeric_code INTEGER PRIMARY KEY,
+    alpha_code TEXT NOT NULL UNIQUE,
+    minor_units INTEGER NOT NULL,
+    symbol TEXT
+);
+
+INSERT INTO currencies (numeric_code, alpha_code, minor_units, symbol) VALUES
+    (8, 'ALL', 2, 'L'),
+    (12, 'DZD', 2, NULL),
+    (32, 'ARS', 2, '$'),
+    (36, 'AUD', 2, 'A$'),
+    (44, 'BSD', 2, '$'),
+    (48, 'BHD', 3, NULL),
+    (50, 'BDT', 2, '৳'),
+    (51, 'AMD', 2, '֏'),
+    (52, 'BBD', 2, '$'),
+    (60, 'BMD', 2, '$'),
+    (64, 'BTN', 2, NULL),
+    (68, 'BOB', 2, 'Bs'),
+    (72, 'BWP', 2, 'P'),
+    (84, 'BZD', 2, '$'),
+    (90, 'SBD', 2, '$'),
+    (96, 'BND', 2, '$'),
+    (104, 'MMK', 2, 'K'),
+    (108, 'BIF', 0, NULL),
+    (116, 'KHR', 2, '៛'),
+    (124, 'CAD', 2, 'C$'),
+    (132, 'CVE', 2, NULL),
+    (136, 'KYD', 2, '$'),
+    (144, 'LKR', 2, 'Rs'),
+    (152, 'CLP', 0, '$'),
+    (156, 'CNY', 2, '¥'),
+    (170, 'COP', 2, '$'),
+    (174, 'KMF', 0, NULL),
+    (188, 'CRC', 2, '₡'),
+    (192, 'CUP', 2, '$'),
+    (203, 'CZK', 2, 'Kč'),
+    (208, 'DKK', 2, 'kr'),
+    (214, 'DOP', 2, '$'),
+    (230, 'ETB', 2, NULL),
+    (232, 'ERN', 2, NULL),
+    (238, 'FKP', 2, '£'),
+    (242, 'FJD', 2, '$'),
+    (262, 'DJF', 0, NULL),
+    (270, 'GMD', 2, NULL),
+    (292, 'GIP', 2, '£'),
+    (320, 'GTQ', 2, 'Q'),
+    (324, 'GNF', 0, NULL),
+    (328, 'GYD', 2, '$'),
+    (332, 'HTG', 2, NULL),
+    (340, 'HNL', 2, 'L'),
+    (344, 'HKD', 2, 'HK$'),
+    (348, 'HUF', 2, 'Ft'),
+    (352, 'ISK', 0, 'kr'),
+    (356, 'INR', 2, '₹'),
+    (360, 'IDR', 2, 'Rp'),
+    (364, 'IRR', 2, '﷼'),
+    (368, 'IQD', 3, NULL),
+    (376, 'ILS', 2, '₪'),
+    (388, 'JMD', 2, '$'),
+    (392, 'JPY', 0, '¥'),
+    (398, 'KZT', 2, '₸'),
+    (400, 'JOD', 3, NULL),
+    (404, 'KES', 2, NULL),
+    (408, 'KPW', 2, '₩'),
+    (410, 'KRW', 0, '₩'),
+    (414, 'KWD', 3, NULL),
+    (417, 'KGS', 2, 'с'),
+    (418, 'LAK', 2, '₭'),
+    (422, 'LBP', 2, NULL),
+    (426, 'LSL', 2, NULL),
+    (430, 'LRD', 2, '$'),
+    (434, 'LYD', 3, NULL),
+    (446, 'MOP', 2, NULL),
+    (454, 'MWK', 2, NULL),
+    (458, 'MYR', 2, 'RM'),
+    (462, 'MVR', 2, NULL),
+    (480, 'MUR', 2, 'Rs'),
+    (484, 'MXN', 2, '$'),
+    (496, 'MNT', 2, '₮'),
+    (498, 'MDL', 2, 'L'),
+    (504, 'MAD', 2, NULL),
+    (512, 'OMR', 3, NULL),
+    (516, 'NAD', 2, '$'),
+    (524, 'NPR', 2, 'Rs'),
+    (532, 'ANG', 2, 'ƒ'),
+    (533, 'AWG', 2, 'ƒ'),
+    (548, 'VUV', 0, NULL),
+    (554, 'NZD', 2, 'NZ$'),
+    (558, 'NIO', 2, NULL),
+    (566, 'NGN', 2, '₦'),
+    (578, 'NOK', 2, 'kr'),
+    (586, 'PKR', 2, 'Rs'),
+    (590, 'PAB', 2, NULL),
+    (598, 'PGK', 2, NULL),
+    (600, 'PYG', 0, '₲'),
+    (604, 'PEN', 2, 'S/'),
+    (608, 'PHP', 2, '₱'),
+    (634, 'QAR', 2, NULL),
+    (643, 'RUB', 2, '₽'),
+    (646, 'RWF', 0, NULL),
+    (654, 'SHP', 2, '£'),
+    (682, 'SAR', 2, NULL),
+    (690, 'SCR', 2, NULL),
+    (702, 'SGD', 2, 'S$'),
+    (704, 'VND', 0, '₫'),
+    (706, 'SOS', 2, NULL),
+    (710, 'ZAR', 2, 'R'),
+    (728, 'SSP', 2, NULL),
+    (748, 'SZL', 2, NULL),
+    (752, 'SEK', 2, 'kr'),
+    (756, 'CHF', 2, 'CHF'),
+    (760, 'SYP', 2, NULL),
+    (764, 'THB', 2, '฿'),
+    (776, 'TOP', 2, NULL),
+    (780, 'TTD', 2, '$'),
+    (784, 'AED', 2, 'د.إ'),
+    (788, 'TND', 3, NULL),
+    (800, 'UGX', 0, NULL),
+    (807, 'MKD', 2, 'ден'),
+    (818, 'EGP', 2, 'E£'),
+    (826, 'GBP', 2, '£'),
+    (834, 'TZS', 2, NULL),
+    (840, 'USD', 2, '$'),
+    (858, 'UYU', 2, '$'),
+    (860, 'UZS', 2, NULL),
+    (882, 'WST', 2, NULL),
+    (886, 'YER', 2, NULL),
+    (901, 'TWD', 2, 'NT$'),
+    (924, 'ZWG', 2, NULL),
+    (925, 'SLE', 2, NULL),
+    (928, 'VES', 2, NULL),
+    (929, 'MRU', 2, NULL),
+    (930, 'STN', 2, NULL),
+    (933, 'BYN', 2, 'Br'),
+    (934, 'TMT', 2, NULL),
+    (936, 'GHS', 2, '₵'),
+    (938, 'SDG', 2, NULL),
+    (941, 'RSD', 2, 'дин'),
+    (943, 'MZN', 2, NULL),
+    (944, 'AZN', 2, '₼'),
+    (946, 'RON', 2, 'lei'),
+    (949, 'TRY', 2, '₺'),
+    (950, 'XAF', 0, NULL),
+    (951, 'XCD', 2, '$'),
+    (952, 'XOF', 0, NULL),
+    (953, 'XPF', 0, NULL),
+    (967, 'ZMW', 2, NULL),
+    (968, 'SRD', 2, '$'),
+    (969, 'MGA', 2, NULL),
+    (971, 'AFN', 2, '؋'),
+    (972, 'TJS', 2, NULL),
+    (973, 'AOA', 2, 'Kz'),
+    (975, 'BGN', 2, 'лв'),
+    (976, 'CDF', 2, NULL),
+    (977, 'BAM', 2, 'KM'),
+    (978, 'EUR', 2, '€'),
+    (980, 'UAH', 2, '₴'),
+    (981, 'GEL', 2, '₾'),
+    (985, 'PLN', 2, 'zł'),
+    (986, 'BRL', 2, 'R$')
+ON CONFLICT (numeric_code) DO NOTHING;
@@ -0,0 +1,15 @@
+-- No foreign keys on purpose: annotations outlive deleted and re-fetched statement items.
+CREATE TABLE IF NOT EXISTS transaction_annotations (
+    statement_item_id TEXT PRIMARY KEY,
+    note TEXT NOT NULL,
+    updated_at TIMESTAMP NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS transaction_tags (
+    statement_item_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (statement_item_id, tag)
+);
+
+CREATE INDEX IF NOT EXISTS transaction_tags_tag_idx ON transaction_tags (tag);
@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS internal_transfers (
+    outflow_item_id TEXT PRIMARY KEY,
+    inflow_item_id TEXT NOT NULL UNIQUE,
+    matched_by TEXT NOT NULL,
+    matched_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (outflow_item_id) REFERENCES statement_items(id) ON DELETE CASCADE,
+    FOREIGN KEY (inflow_item_id) REFERENCES statement_items(id) ON DELETE CASCADE
+);
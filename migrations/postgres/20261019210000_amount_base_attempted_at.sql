@@ -0,0 +1 @@
+ALTER TABLE statement_items ADD COLUMN amount_base_attempted_at TIMESTAMP WITHOUT TIME ZONE;
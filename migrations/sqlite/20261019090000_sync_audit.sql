@@ -0,0 +1,28 @@
+CREATE TABLE IF NOT EXISTS sync_runs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    token_fingerprint TEXT NOT NULL,
+    started_at TIMESTAMP NOT NULL,
+    finished_at TIMESTAMP,
+    accounts_touched BIGINT NOT NULL DEFAULT 0,
+    windows_fetched BIGINT NOT NULL DEFAULT 0,
+    items_fetched BIGINT NOT NULL DEFAULT 0,
+    items_inserted BIGINT NOT NULL DEFAULT 0,
+    errors TEXT
+);
+
+CREATE TABLE IF NOT EXISTS sync_windows (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    run_id BIGINT NOT NULL,
+    account_id TEXT NOT NULL,
+    window_start TIMESTAMP NOT NULL,
+    window_end TIMESTAMP,
+    status TEXT NOT NULL,
+    items_fetched BIGINT NOT NULL DEFAULT 0,
+    items_inserted BIGINT NOT NULL DEFAULT 0,
+    error TEXT,
+    fetched_at TIMESTAMP NOT NULL,
+    FOREIGN KEY (run_id) REFERENCES sync_runs(id),
+    FOREIGN KEY (account_id) REFERENCES accounts(id)
+);
+
+CREATE INDEX IF NOT EXISTS sync_windows_account_id_window_start_idx ON sync_windows (account_id, window_start);
@@ -0,0 +1,6 @@
+CREATE TABLE IF NOT EXISTS instance_lock (
+    name TEXT PRIMARY KEY,
+    holder TEXT NOT NULL,
+    acquired_at TIMESTAMP NOT NULL,
+    heartbeat_at TIMESTAMP NOT NULL
+);
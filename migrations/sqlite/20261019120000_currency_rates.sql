@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS currency_rates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    currency_code_a INTEGER NOT NULL,
+    currency_code_b INTEGER NOT NULL,
+    date TIMESTAMP NOT NULL,
+    rate_buy REAL,
+    rate_sell REAL,
+    rate_cross REAL,
+    fetched_at TIMESTAMP NOT NULL,
+    UNIQUE (currency_code_a, currency_code_b, date)
+);
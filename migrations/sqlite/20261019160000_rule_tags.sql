@@ -0,0 +1,8 @@
+CREATE TABLE IF NOT EXISTS statement_item_tags (
+    statement_item_id TEXT NOT NULL,
+    tag TEXT NOT NULL,
+    PRIMARY KEY (statement_item_id, tag),
+    FOREIGN KEY (statement_item_id) REFERENCES statement_items(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS statement_item_tags_tag_idx ON statement_item_tags (tag);
@@ -0,0 +1,8 @@
+-- Items already written to each journal, so incremental exports only append new ones.
+CREATE TABLE IF NOT EXISTS journal_exports (
+    format TEXT NOT NULL,
+    target TEXT NOT NULL,
+    statement_item_id TEXT NOT NULL,
+    exported_at TIMESTAMP NOT NULL,
+    PRIMARY KEY (format, target, statement_item_id)
+);
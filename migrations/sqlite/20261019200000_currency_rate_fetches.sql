@@ -0,0 +1,7 @@
+-- Every fetch of the public rates, so the fetch interval holds even when no rate was new.
+CREATE TABLE IF NOT EXISTS currency_rate_fetches (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    fetched_at TIMESTAMP NOT NULL,
+    pairs INTEGER NOT NULL,
+    new_rates INTEGER NOT NULL
+);
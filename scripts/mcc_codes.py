@@ -0,0 +1,92 @@
+#!/usr/bin/env python3
+"""Writes the migrations for every backend that fill `mcc_codes` from data/mcc_codes.csv.
+
+Without arguments this regenerates 20261019150000_mcc_codes, which creates the table and
+seeds it. Applied migrations can't change, so after editing the CSV run this with a new
+migration name, e.g. `python3 scripts/mcc_codes.py 20261101000000_mcc_codes_update`, to
+get one that only upserts the rows.
+"""
+
+import csv
+import sys
+from pathlib import Path
+
+ROOT = Path(__file__).resolve().parent.parent
+
+SEED = "20261019150000_mcc_codes"
+
+HEADER = "-- Generated by scripts/mcc_codes.py from data/mcc_codes.csv, edit those instead.\n"
+
+TABLES = {
+    "postgres": """CREATE TABLE IF NOT EXISTS mcc_codes (
+    code INTEGER PRIMARY KEY,
+    description TEXT NOT NULL,
+    iso_group TEXT NOT NULL
+);
+""",
+    "sqlite": """CREATE TABLE IF NOT EXISTS mcc_codes (
+    code INTEGER PRIMARY KEY,
+    description TEXT NOT NULL,
+    iso_group TEXT NOT NULL
+);
+""",
+    "mysql": """CREATE TABLE IF NOT EXISTS mcc_codes (
+    code INTEGER PRIMARY KEY,
+    description VARCHAR(255) NOT NULL,
+    iso_group VARCHAR(255) NOT NULL
+);
+""",
+}
+
+CATEGORY_COLUMNS = {
+    "postgres": "ALTER TABLE statement_items ADD COLUMN category TEXT;\n",
+    "sqlite": "ALTER TABLE statement_items ADD COLUMN category TEXT;\n",
+    "mysql": "ALTER TABLE statement_items ADD COLUMN category VARCHAR(255);\n",
+}
+
+UPSERTS = {
+    "postgres": (
+        "INSERT INTO mcc_codes (code, description, iso_group) VALUES\n",
+        "\nON CONFLICT (code) DO UPDATE SET description = EXCLUDED.description, iso_group = EXCLUDED.iso_group;\n",
+    ),
+    "sqlite": (
+        "INSERT INTO mcc_codes (code, description, iso_group) VALUES\n",
+        "\nON CONFLICT (code) DO UPDATE SET description = excluded.description, iso_group = excluded.iso_group;\n",
+    ),
+    "mysql": (
+        "INSERT INTO mcc_codes (code, description, iso_group) VALUES\n",
+        "\nON DUPLICATE KEY UPDATE description = VALUES(description), iso_group = VALUES(iso_group);\n",
+    ),
+}
+
+
+def quoted(text):
+    return "'" + text.replace("'", "''") + "'"
+
+
+def main():
+    if len(sys.argv) > 2:
+        sys.exit(f"usage: {sys.argv[0]} [<version>_<name>]")
+    name = sys.argv[1] if len(sys.argv) == 2 else SEED
+    with open(ROOT / "data" / "mcc_codes.csv", newline="") as file:
+        rows = list(csv.DictReader(file))
+    codes = [int(row["code"]) for row in rows]
+    if codes != sorted(set(codes)):
+        sys.exit("data/mcc_codes.csv must list every code once, in order")
+    values = ",\n".join(
+        f"    ({row['code']}, {quoted(row['description'])}, {quoted(row['group'])})"
+        for row in rows
+    )
+    for backend, (insert, conflict) in UPSERTS.items():
+        upsert = insert + values + conflict
+        if name == SEED:
+            sql = HEADER + TABLES[backend] + "\n" + upsert + "\n" + CATEGORY_COLUMNS[backend]
+        else:
+            sql = HEADER + upsert
+        path = ROOT / "migrations" / backend / f"{name}.sql"
+        path.write_text(sql)
+        print(path.relative_to(ROOT))
+
+
+if __name__ == "__main__":
+    main()
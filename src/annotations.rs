@@ -0,0 +1,122 @@
+use crate::db_types::DatabasePool;
+use crate::{crud, models, utils};
+
+/// What a user added to a statement item.
+///
+/// Annotations live in `transaction_annotations` and `transaction_tags`, which syncing
+/// never writes to and which have no foreign keys, so they survive `reset --delete-items`
+/// and the re-fetch that follows.
+#[derive(Debug, Clone, Default)]
+pub struct Annotation {
+    pub note: Option<models::TransactionAnnotation>,
+    pub tags: Vec<String>,
+}
+
+pub async fn get(pool: &DatabasePool, item_id: &str) -> Result<Annotation, sqlx::Error> {
+    let note = crud::get_annotation(pool, item_id).await?;
+    let tags = crud::get_transaction_tags(pool, item_id).await?;
+    Ok(Annotation { note, tags })
+}
+
+pub async fn set_note(pool: &DatabasePool, item_id: &str, note: &str) -> Result<(), sqlx::Error> {
+    let now = utils::datetime_from(utils::current_timestamp());
+    crud::upsert_annotation(pool, item_id, note, now).await
+}
+
+/// Returns whether the item had a note.
+pub async fn clear_note(pool: &DatabasePool, item_id: &str) -> Result<bool, sqlx::Error> {
+    crud::delete_annotation(pool, item_id).await
+}
+
+/// Returns whether the tag is new on the item.
+pub async fn add_tag(pool: &DatabasePool, item_id: &str, tag: &str) -> Result<bool, sqlx::Error> {
+    let now = utils::datetime_from(utils::current_timestamp());
+    crud::insert_transaction_tag(pool, item_id, tag, now).await
+}
+
+/// Returns whether the item had the tag.
+pub async fn remove_tag(
+    pool: &DatabasePool,
+    item_id: &str,
+    tag: &str,
+) -> Result<bool, sqlx::Error> {
+    crud::delete_transaction_tag(pool, item_id, tag).await
+}
+
+/// A change to a statement item's annotation.
+#[derive(Debug, Clone)]
+pub enum Edit {
+    SetNote(String),
+    ClearNote,
+    AddTags(Vec<String>),
+    RemoveTags(Vec<String>),
+}
+
+/// Applies `edit` and logs what changed, warning when the item is not stored (yet).
+pub async fn apply(pool: &DatabasePool, item_id: &str, edit: Edit) {
+    if !check_item(pool, item_id).await {
+        return;
+    }
+    match edit {
+        Edit::SetNote(note) => match set_note(pool, item_id, &note).await {
+            Ok(()) => tracing::info!(item_id, "Note saved"),
+            Err(e) => tracing::error!("Failed to save note: {:?}", e),
+        },
+        Edit::ClearNote => match clear_note(pool, item_id).await {
+            Ok(true) => tracing::info!(item_id, "Note removed"),
+            Ok(false) => tracing::info!(item_id, "Statement item has no note"),
+            Err(e) => tracing::error!("Failed to remove note: {:?}", e),
+        },
+        Edit::AddTags(tags) => {
+            for tag in tags {
+                match add_tag(pool, item_id, &tag).await {
+                    Ok(true) => tracing::info!(item_id, tag, "Tag added"),
+                    Ok(false) => tracing::info!(item_id, tag, "Statement item already has the tag"),
+                    Err(e) => tracing::error!("Failed to add tag {}: {:?}", tag, e),
+                }
+            }
+        }
+        Edit::RemoveTags(tags) => {
+            for tag in tags {
+                match remove_tag(pool, item_id, &tag).await {
+                    Ok(true) => tracing::info!(item_id, tag, "Tag removed"),
+                    Ok(false) => tracing::info!(item_id, tag, "Statement item has no such tag"),
+                    Err(e) => tracing::error!("Failed to remove tag {}: {:?}", tag, e),
+                }
+            }
+        }
+    }
+}
+
+pub async fn show(pool: &DatabasePool, item_id: &str) {
+    if !check_item(pool, item_id).await {
+        return;
+    }
+    match get(pool, item_id).await {
+        Ok(annotation) => tracing::info!(
+            item_id,
+            note = annotation.note.as_ref().map_or("none", |n| n.note.as_str()),
+            note_updated_at = annotation.note.as_ref().map(|n| n.updated_at.to_string()),
+            tags = annotation.tags.join(","),
+            "Annotation",
+        ),
+        Err(e) => tracing::error!("Failed to get annotation: {:?}", e),
+    }
+}
+
+async fn check_item(pool: &DatabasePool, item_id: &str) -> bool {
+    match crud::statement_item_exists(pool, item_id).await {
+        Ok(true) => true,
+        Ok(false) => {
+            tracing::warn!(
+                item_id,
+                "Statement item is not stored, annotations apply once it is synced"
+            );
+            true
+        }
+        Err(e) => {
+            tracing::error!("Failed to look up statement item {}: {:?}", item_id, e);
+            false
+        }
+    }
+}
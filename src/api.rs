@@ -1,94 +1,197 @@
-use crate::schema::{ClientInfo, StatementItem};
+use crate::schema::{ClientInfo, CurrencyRate, StatementItem};
+use crate::shutdown::Shutdown;
 use rand::Rng;
-use reqwest::blocking::Client;
-use std::{thread, time::Duration};
+use reqwest::Client;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio::time::{self, Instant};
 
 const MONOBANK_API_URL: &str = "https://api.monobank.ua/personal";
+const MONOBANK_BANK_URL: &str = "https://api.monobank.ua/bank";
 const MAX_TIME_DIFF_SEC: u32 = 31 * 24 * 60 * 60; // 31 days
 pub const WAIT_TIME_SEC: u32 = 60;
 pub const WAIT_JITTER_SEC: u32 = 5;
 
+fn next_window(last_success_time: u32, end_time: u32) -> (u32, u32) {
+    let start = last_success_time + 1;
+    let end = std::cmp::min(start + MAX_TIME_DIFF_SEC, end_time);
+    (start, end)
+}
+
+/// Windows `FetchingStatements` will request after `last_success_time`, not counting 500-item splits.
+pub fn plan_windows(last_success_time: u32, end_time: u32) -> Vec<(u32, u32)> {
+    let mut windows = Vec::new();
+    let mut last = last_success_time;
+    while last < end_time {
+        let window = next_window(last, end_time);
+        windows.push(window);
+        last = window.1;
+    }
+    windows
+}
+
+/// Rough time the rate limiting makes `requests` requests take.
+pub fn estimate_wait_sec(requests: u32) -> u32 {
+    requests * (WAIT_TIME_SEC + WAIT_JITTER_SEC / 2)
+}
+
+/// Spaces statement requests out, remembering the last one across iterators and sync runs.
 #[derive(Debug)]
-pub struct FetchingStatementsIterator<'a> {
-    pub client: &'a Client,
-    pub token: String,
-    pub account_id: String,
-    pub last_success_time: u32,
-    pub end_time: u32,
+pub struct RateLimiter {
     pub wait_length_sec: u32,
     pub wait_jitter_sec: u32,
+    shutdown: Shutdown,
+    last_request: Mutex<Option<Instant>>,
 }
 
-impl<'a> FetchingStatementsIterator<'a> {
-    fn calculate_next_window(&self) -> (u32, u32) {
-        let start = self.last_success_time + 1;
-        let end = std::cmp::min(start + MAX_TIME_DIFF_SEC, self.end_time);
-        (start, end)
+impl RateLimiter {
+    pub fn new(wait_length_sec: u32, wait_jitter_sec: u32, shutdown: Shutdown) -> Self {
+        Self {
+            wait_length_sec,
+            wait_jitter_sec,
+            shutdown,
+            last_request: Mutex::new(None),
+        }
     }
 
-    fn sleep_with_jitter(&self) {
+    /// Returns `false` without waiting out the rest when a shutdown was requested meanwhile.
+    async fn sleep_with_jitter(&self) -> bool {
         let jitter = rand::thread_rng().gen_range(0..self.wait_jitter_sec * 1000);
-        let sleep_time = {
+        let interval = {
             Duration::from_secs(self.wait_length_sec as u64) + Duration::from_millis(jitter as u64)
         };
+        let mut last_request = self.last_request.lock().await;
+        // Nothing is known about requests made before we started, so the first one waits too
+        let sleep_time = match *last_request {
+            Some(last) => interval.saturating_sub(last.elapsed()),
+            None => interval,
+        };
         tracing::debug!(
             seconds = sleep_time.as_secs(),
             "Sleeping before next request",
         );
-        thread::sleep(sleep_time);
+        tokio::select! {
+            biased;
+            _ = self.shutdown.wait() => return false,
+            _ = time::sleep(sleep_time) => {}
+        }
+        *last_request = Some(Instant::now());
+        true
     }
+}
 
-    fn fetch_next_batch(&self, start: u32, end: u32) -> Result<Vec<StatementItem>, reqwest::Error> {
-        self.sleep_with_jitter();
+/// Fetches an account's statements window by window, waiting out the rate limit before each request.
+#[derive(Debug)]
+pub struct FetchingStatements<'a> {
+    pub client: &'a Client,
+    pub rate_limiter: &'a RateLimiter,
+    pub token: String,
+    pub account_id: String,
+    pub last_success_time: u32,
+    pub end_time: u32,
+}
+
+impl<'a> FetchingStatements<'a> {
+    fn calculate_next_window(&self) -> (u32, u32) {
+        next_window(self.last_success_time, self.end_time)
+    }
+
+    /// `None` when a shutdown interrupted the wait before the request was made.
+    async fn fetch_next_batch(
+        &self,
+        start: u32,
+        end: u32,
+    ) -> Result<Option<Vec<StatementItem>>, reqwest::Error> {
+        if !self.rate_limiter.sleep_with_jitter().await {
+            return Ok(None);
+        }
         fetch_statements(self.client, &self.account_id, start, end, &self.token)
+            .await
+            .map(Some)
     }
 
-    fn try_fetch(&self, start: u32, end: u32) -> Result<Vec<StatementItem>, reqwest::Error> {
-        let result = self.fetch_next_batch(start, end);
-        match result {
-            Ok(data) if data.len() == 500 => {
-                tracing::warn!(
-                    "Timerange has exactly 500 statements,  retrying with smaller window"
-                );
-                let delta = end - start;
-                if delta < 2 {
-                    return Ok(data);
-                };
-                self.try_fetch(start, start + delta / 2)
+    /// Returns the end of the window that was actually fetched alongside its items,
+    /// which is earlier than `end` when the window had to be split.
+    async fn try_fetch(
+        &self,
+        start: u32,
+        mut end: u32,
+    ) -> Result<Option<(u32, Vec<StatementItem>)>, reqwest::Error> {
+        loop {
+            match self.fetch_next_batch(start, end).await? {
+                Some(data) if data.len() == 500 && end - start >= 2 => {
+                    tracing::warn!(
+                        "Timerange has exactly 500 statements,  retrying with smaller window"
+                    );
+                    end = start + (end - start) / 2;
+                }
+                data => return Ok(data.map(|data| (end, data))),
             }
-            _ => result,
         }
     }
-}
-impl<'a> Iterator for FetchingStatementsIterator<'a> {
-    type Item = Result<(u32, Vec<StatementItem>), (u32, reqwest::Error)>;
 
-    fn next(&mut self) -> Option<Self::Item> {
+    /// The next window's end and items, `None` once `end_time` is reached or a shutdown
+    /// was requested. Errors come with the time fetched up to before them.
+    pub async fn fetch_next(
+        &mut self,
+    ) -> Option<Result<(u32, Vec<StatementItem>), (u32, reqwest::Error)>> {
         if self.last_success_time >= self.end_time {
             return None;
         }
 
         let (start, end) = self.calculate_next_window();
-        match self.try_fetch(start, end) {
-            Ok(data) => {
-                self.last_success_time = std::cmp::min(end, self.end_time);
+        match self.try_fetch(start, end).await {
+            Ok(Some((fetched_until, data))) => {
+                self.last_success_time = std::cmp::min(fetched_until, self.end_time);
                 Some(Ok((self.last_success_time, data)))
             }
+            Ok(None) => {
+                tracing::info!(
+                    account_id = self.account_id,
+                    "Shutdown requested, not fetching further windows"
+                );
+                None
+            }
             Err(e) => Some(Err((self.last_success_time, e))),
         }
     }
 }
 
-pub fn fetch_client_info(client: &Client, token: &str) -> Result<ClientInfo, reqwest::Error> {
+pub async fn fetch_client_info(client: &Client, token: &str) -> Result<ClientInfo, reqwest::Error> {
     let url = format!("{MONOBANK_API_URL}/client-info");
     tracing::info!(token = token, "Getting client data...");
-    let response = client.get(url).header("X-Token", token).send()?;
+    let response = client.get(url).header("X-Token", token).send().await?;
     tracing::debug!("Deserializing client data...");
-    let client_info = response.json::<ClientInfo>()?;
+    let client_info = response.json::<ClientInfo>().await?;
     Ok(client_info)
 }
 
-pub fn fetch_statements(
+/// Public rates, cached by Monobank for a few minutes and rate-limited per IP.
+pub async fn fetch_currency_rates(client: &Client) -> Result<Vec<CurrencyRate>, reqwest::Error> {
+    let url = format!("{MONOBANK_BANK_URL}/currency");
+    tracing::info!("Getting currency rates...");
+    let response = client.get(url).send().await?.error_for_status()?;
+    tracing::debug!("Deserializing currency rates...");
+    let rates = response.json::<Vec<CurrencyRate>>().await?;
+    Ok(rates)
+}
+
+/// Points Monobank's `StatementItem` pushes for this token at `url`, an empty one disables them.
+/// Monobank checks the URL with a GET request before accepting it.
+pub async fn set_webhook(client: &Client, token: &str, url: &str) -> Result<(), reqwest::Error> {
+    let endpoint = format!("{MONOBANK_API_URL}/webhook");
+    tracing::info!(token = token, url = url, "Setting webhook...");
+    client
+        .post(endpoint)
+        .header("X-Token", token)
+        .json(&serde_json::json!({ "webHookUrl": url }))
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(())
+}
+
+pub async fn fetch_statements(
     client: &Client,
     resource_id: &str,
     from: u32,
@@ -103,8 +206,8 @@ pub fn fetch_statements(
         token = token,
         "Getting statements...",
     );
-    let response = client.get(url).header("X-Token", token).send()?;
+    let response = client.get(url).header("X-Token", token).send().await?;
     tracing::debug!("Deserializing statements...");
-    let statement_items = response.json::<Vec<StatementItem>>()?;
+    let statement_items = response.json::<Vec<StatementItem>>().await?;
     Ok(statement_items)
 }
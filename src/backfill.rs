@@ -0,0 +1,230 @@
+use crate::sync::{self, AccountPlan, Progress, Syncer};
+use crate::{crud, utils};
+use serde_with::chrono::NaiveDateTime;
+
+/// Re-fetches history below each account's cursor without moving it.
+///
+/// With neither `from` nor `to`, the ranges between `since` (or the account's first
+/// successful `sync_windows` row) and the cursor that no successful window covers are
+/// detected and fetched, see `find_gaps_after_log`.
+pub async fn run(
+    syncer: &Syncer,
+    account_id: Option<String>,
+    from: Option<u32>,
+    to: Option<u32>,
+    since: Option<u32>,
+    time_floor: NaiveDateTime,
+) {
+    let account_ids = match account_id {
+        Some(id) => vec![id],
+        None => match crud::get_account_ids(&syncer.pool).await {
+            Ok(ids) => ids,
+            Err(e) => {
+                tracing::error!("Failed to list accounts: {:?}", e);
+                return;
+            }
+        },
+    };
+    let floor = utils::timestamp_from(time_floor);
+    for account_id in account_ids {
+        let token = match crud::get_account_token(&syncer.pool, &account_id).await {
+            Ok(Some(token)) => token,
+            Ok(None) => {
+                tracing::error!(account_id, "Unknown account, run a regular sync first");
+                continue;
+            }
+            Err(e) => {
+                tracing::error!("Failed to look up token for {}: {:?}", account_id, e);
+                continue;
+            }
+        };
+        let cursor = match crud::get_last_sync_time(&syncer.pool, account_id.clone()).await {
+            Ok(Some(last_sync_at)) => utils::timestamp_from(last_sync_at),
+            Ok(None) => {
+                tracing::info!(account_id, "Account was never synced, nothing to backfill");
+                continue;
+            }
+            Err(e) => {
+                tracing::error!("Failed to get sync time for {}: {:?}", account_id, e);
+                continue;
+            }
+        };
+        let ranges = if from.is_none() && to.is_none() {
+            let windows = match crud::get_successful_windows(&syncer.pool, &account_id).await {
+                Ok(windows) => windows,
+                Err(e) => {
+                    tracing::error!("Failed to get sync windows for {}: {:?}", account_id, e);
+                    continue;
+                }
+            };
+            let covered = windows
+                .into_iter()
+                .filter_map(|w| {
+                    w.window_end.map(|end| {
+                        (
+                            utils::timestamp_from(w.window_start),
+                            utils::timestamp_from(end),
+                        )
+                    })
+                })
+                .collect::<Vec<(u32, u32)>>();
+            let log_start = covered.iter().map(|(start, _)| *start).min();
+            let Some(start) = since.or(log_start) else {
+                tracing::info!(
+                    account_id,
+                    "No successful windows recorded, pass --since to detect gaps from a day on"
+                );
+                continue;
+            };
+            let mut first_item = None;
+            if let Some(log_start) = log_start.filter(|&log_start| start < log_start) {
+                let before_log = (
+                    utils::datetime_from(start),
+                    utils::datetime_from(log_start - 1),
+                );
+                match crud::get_statement_items(&syncer.pool, Some(&account_id), Some(before_log))
+                    .await
+                {
+                    Ok(items) => {
+                        first_item = items.first().map(|item| utils::timestamp_from(item.time))
+                    }
+                    Err(e) => {
+                        tracing::error!("Failed to get statement items of {}: {:?}", account_id, e);
+                        continue;
+                    }
+                }
+            }
+            find_gaps_after_log(covered, start, cursor, first_item)
+        } else {
+            let start = from.unwrap_or(floor);
+            let end = to.unwrap_or(cursor);
+            if end > cursor {
+                tracing::warn!(
+                    account_id,
+                    "Backfill range ends after the sync cursor, the regular sync will fetch the rest"
+                );
+            }
+            let end = end.min(cursor);
+            if start <= end {
+                vec![(start, end)]
+            } else {
+                vec![]
+            }
+        };
+        if ranges.is_empty() {
+            tracing::info!(account_id, "No gaps to backfill");
+            continue;
+        }
+        tracing::info!(account_id, ranges = ranges.len(), "Backfilling...");
+        let plans = ranges
+            .into_iter()
+            .map(|(start, end)| AccountPlan {
+                token: token.clone(),
+                account_id: account_id.clone(),
+                cursor: start - 1,
+                end_time: end,
+            })
+            .collect::<Vec<AccountPlan>>();
+        let mut progress = Progress::new(&plans);
+        let mut log = syncer.start_run(&token).await;
+        log.run.accounts_touched = 1;
+        for plan in &plans {
+            if syncer.shutdown.requested() {
+                break;
+            }
+            tracing::info!(
+                account_id,
+                from = %utils::datetime_from(plan.cursor + 1),
+                to = %utils::datetime_from(plan.end_time),
+                "Backfilling range",
+            );
+            syncer
+                .fetch_windows(&mut log, plan, &mut progress, false)
+                .await;
+        }
+        let run = syncer.finish_run(log).await;
+        sync::log_summary(&[run], syncer.shutdown.requested());
+        if syncer.shutdown.requested() {
+            return;
+        }
+    }
+    syncer.sinks.export_stored(&syncer.pool).await;
+}
+
+/// Gaps in `from..=to` like `find_gaps`, counting history older than the recorded
+/// windows as covered from `first_item` on, the time of the account's first stored item.
+///
+/// Versions that recorded no windows synced everything from their first item up to
+/// where the log starts, so only a range before that item is a gap there.
+pub fn find_gaps_after_log(
+    mut covered: Vec<(u32, u32)>,
+    from: u32,
+    to: u32,
+    first_item: Option<u32>,
+) -> Vec<(u32, u32)> {
+    let log_start = covered.iter().map(|(start, _)| *start).min();
+    if let (Some(first_item), Some(log_start)) = (first_item, log_start) {
+        if first_item < log_start {
+            covered.push((first_item, log_start - 1));
+        }
+    }
+    find_gaps(covered, from, to)
+}
+
+/// Splits `from..=to` into the ranges not covered by any of the inclusive `covered` windows.
+pub fn find_gaps(mut covered: Vec<(u32, u32)>, from: u32, to: u32) -> Vec<(u32, u32)> {
+    covered.sort_unstable();
+    let mut gaps = Vec::new();
+    let mut position = from;
+    for (start, end) in covered {
+        if position > to {
+            break;
+        }
+        if start > position {
+            gaps.push((position, (start - 1).min(to)));
+        }
+        position = position.max(end.saturating_add(1));
+    }
+    if position <= to {
+        gaps.push((position, to));
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_covered_is_one_gap() {
+        assert_eq!(find_gaps(vec![], 100, 200), [(100, 200)]);
+    }
+
+    #[test]
+    fn gaps_are_between_unsorted_and_overlapping_windows() {
+        let covered = vec![(150, 160), (90, 120), (110, 130), (190, 250)];
+        assert_eq!(find_gaps(covered, 100, 200), [(131, 149), (161, 189)]);
+    }
+
+    #[test]
+    fn adjacent_windows_leave_no_gap() {
+        let covered = vec![(100, 149), (150, 200)];
+        assert!(find_gaps(covered, 100, 200).is_empty());
+    }
+
+    #[test]
+    fn history_from_the_first_item_before_the_log_is_covered() {
+        let covered = vec![(150, 200)];
+        assert_eq!(
+            find_gaps_after_log(covered.clone(), 100, 200, Some(120)),
+            [(100, 119)]
+        );
+        assert_eq!(find_gaps_after_log(covered, 100, 200, None), [(100, 149)]);
+    }
+
+    #[test]
+    fn windows_outside_the_range_are_ignored() {
+        let covered = vec![(0, 50), (300, 400)];
+        assert_eq!(find_gaps(covered, 100, 200), [(100, 200)]);
+    }
+}
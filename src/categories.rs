@@ -0,0 +1,386 @@
+use crate::db_types::DatabasePool;
+use crate::{config, crud, models};
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::{BTreeSet, HashMap};
+use std::fs;
+
+/// Built-in mapping, used unless `CATEGORIES_FILE` points at another one.
+const DEFAULT_CATEGORIES: &str = include_str!("../categories.toml");
+
+#[derive(Debug, Deserialize)]
+struct CategoriesFile {
+    #[serde(default)]
+    mcc: Vec<MccEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+struct MccEntry {
+    category: String,
+    /// Single codes like `5411` or inclusive ranges like `5811-5814`.
+    codes: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RulesFile {
+    #[serde(default)]
+    rule: Vec<RuleEntry>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleEntry {
+    name: Option<String>,
+    #[serde(default)]
+    priority: i32,
+    category: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    description: Option<String>,
+    counter_name: Option<String>,
+    counter_iban: Option<String>,
+    counter_edrpou: Option<String>,
+    mcc: Option<Vec<String>>,
+    amount_min: Option<i64>,
+    amount_max: Option<i64>,
+    account: Option<String>,
+}
+
+/// A rule matches when every condition it sets holds.
+#[derive(Debug, Clone)]
+struct Rule {
+    priority: i32,
+    category: Option<String>,
+    tags: Vec<String>,
+    description: Option<Regex>,
+    counter_name: Option<Regex>,
+    counter_iban: Option<String>,
+    counter_edrpou: Option<String>,
+    mcc_ranges: Option<Vec<(u32, u32)>>,
+    amount_min: Option<i64>,
+    amount_max: Option<i64>,
+    account: Option<String>,
+}
+
+impl Rule {
+    fn from_entry(index: usize, entry: RuleEntry) -> Result<Self, String> {
+        let label = entry.name.unwrap_or_else(|| format!("#{}", index + 1));
+        if entry.category.is_none() && entry.tags.is_empty() {
+            return Err(format!("rule {label} sets neither a category nor tags"));
+        }
+        let regex = |pattern: Option<String>| {
+            pattern
+                .map(|p| Regex::new(&p).map_err(|e| format!("rule {label}: {e}")))
+                .transpose()
+        };
+        let mcc_ranges = entry
+            .mcc
+            .map(|codes| codes.iter().map(|c| parse_mcc_range(c)).collect())
+            .transpose()
+            .map_err(|e| format!("rule {label}: {e}"))?;
+        Ok(Self {
+            priority: entry.priority,
+            category: entry.category,
+            tags: entry.tags,
+            description: regex(entry.description)?,
+            counter_name: regex(entry.counter_name)?,
+            counter_iban: entry.counter_iban,
+            counter_edrpou: entry.counter_edrpou,
+            mcc_ranges,
+            amount_min: entry.amount_min,
+            amount_max: entry.amount_max,
+            account: entry.account,
+        })
+    }
+
+    fn matches(&self, item: &models::StatementItem) -> bool {
+        let pattern = |regex: &Option<Regex>, value: Option<&str>| match regex {
+            Some(regex) => value.is_some_and(|v| regex.is_match(v)),
+            None => true,
+        };
+        let exact = |expected: &Option<String>, value: Option<&str>| match expected {
+            Some(expected) => value == Some(expected.as_str()),
+            None => true,
+        };
+        pattern(&self.description, Some(&item.description))
+            && pattern(&self.counter_name, item.counter_name.as_deref())
+            && exact(&self.counter_iban, item.counter_iban.as_deref())
+            && exact(&self.counter_edrpou, item.counter_edrpou.as_deref())
+            && exact(&self.account, Some(&item.account_id))
+            && self.mcc_ranges.as_ref().is_none_or(|ranges| {
+                ranges
+                    .iter()
+                    .any(|(start, end)| (*start..=*end).contains(&item.mcc))
+            })
+            && self.amount_min.is_none_or(|min| item.amount >= min)
+            && self.amount_max.is_none_or(|max| item.amount <= max)
+    }
+}
+
+/// Assigns our own spending categories and tags to statement items.
+#[derive(Debug, Clone, Default)]
+pub struct Categorizer {
+    mcc_ranges: Vec<(u32, u32, String)>,
+    /// Highest priority first, file order among equals.
+    rules: Vec<Rule>,
+}
+
+impl Categorizer {
+    /// Reads `CATEGORIES_FILE`, or the built-in mapping when it is not set, and the
+    /// rules in `RULES_FILE` if there is one.
+    pub fn load() -> Result<Self, String> {
+        let mut categorizer = match config::get_categories_file() {
+            Some(path) => {
+                Self::from_toml(&read(&path)?).map_err(|e| format!("Invalid {path}: {e}"))?
+            }
+            None => Self::from_toml(DEFAULT_CATEGORIES)?,
+        };
+        if let Some(path) = config::get_rules_file() {
+            categorizer
+                .load_rules(&read(&path)?)
+                .map_err(|e| format!("Invalid {path}: {e}"))?;
+        }
+        Ok(categorizer)
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let file: CategoriesFile = toml::from_str(content).map_err(|e| e.to_string())?;
+        let mut mcc_ranges = Vec::new();
+        for entry in file.mcc {
+            for codes in &entry.codes {
+                let (start, end) = parse_mcc_range(codes)?;
+                mcc_ranges.push((start, end, entry.category.clone()));
+            }
+        }
+        Ok(Self {
+            mcc_ranges,
+            rules: Vec::new(),
+        })
+    }
+
+    fn load_rules(&mut self, content: &str) -> Result<(), String> {
+        let file: RulesFile = toml::from_str(content).map_err(|e| e.to_string())?;
+        self.rules = file
+            .rule
+            .into_iter()
+            .enumerate()
+            .map(|(index, entry)| Rule::from_entry(index, entry))
+            .collect::<Result<_, _>>()?;
+        // Stable, so equal priorities keep the order of the file
+        self.rules
+            .sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+        Ok(())
+    }
+
+    pub fn category_for_mcc(&self, mcc: u32) -> Option<&str> {
+        self.mcc_ranges
+            .iter()
+            .find(|(start, end, _)| (*start..=*end).contains(&mcc))
+            .map(|(_, _, category)| category.as_str())
+    }
+
+    /// Sets the category of the first matching rule that has one, falling back to the
+    /// MCC mapping, and returns the tags of every matching rule.
+    pub fn categorize(&self, item: &mut models::StatementItem) -> Vec<String> {
+        let matching = self
+            .rules
+            .iter()
+            .filter(|rule| rule.matches(item))
+            .collect::<Vec<&Rule>>();
+        item.category = matching
+            .iter()
+            .find_map(|rule| rule.category.clone())
+            .or_else(|| self.category_for_mcc(item.mcc).map(str::to_string));
+        matching
+            .iter()
+            .flat_map(|rule| rule.tags.iter().cloned())
+            .collect::<BTreeSet<String>>()
+            .into_iter()
+            .collect()
+    }
+}
+
+fn read(path: &str) -> Result<String, String> {
+    fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))
+}
+
+fn parse_mcc_range(codes: &str) -> Result<(u32, u32), String> {
+    let parse = |code: &str| {
+        code.trim()
+            .parse::<u32>()
+            .map_err(|_| format!("'{codes}' is not an MCC or an MCC range"))
+    };
+    match codes.split_once('-') {
+        Some((start, end)) => Ok((parse(start)?, parse(end)?)),
+        None => parse(codes).map(|code| (code, code)),
+    }
+}
+
+/// Re-applies the current categories and rules to already stored items, e.g. after editing them.
+pub async fn recategorize(
+    pool: &DatabasePool,
+    categorizer: &Categorizer,
+    account_id: Option<&str>,
+) {
+    let items = match crud::get_statement_items(pool, account_id, None).await {
+        Ok(items) => items,
+        Err(e) => {
+            tracing::error!("Failed to get statement items: {:?}", e);
+            return;
+        }
+    };
+    let mut stored_tags: HashMap<String, Vec<String>> = HashMap::new();
+    match crud::get_rule_tags(pool, account_id).await {
+        Ok(tags) => {
+            for tag in tags {
+                stored_tags
+                    .entry(tag.statement_item_id)
+                    .or_default()
+                    .push(tag.tag);
+            }
+        }
+        Err(e) => {
+            tracing::error!("Failed to get statement item tags: {:?}", e);
+            return;
+        }
+    }
+    let total = items.len();
+    let mut changed = 0;
+    let mut retagged = 0;
+    for mut item in items {
+        let previous = item.category.take();
+        let tags = categorizer.categorize(&mut item);
+        if item.category != previous {
+            match crud::update_category(pool, &item.id, item.category.as_deref()).await {
+                Ok(()) => changed += 1,
+                Err(e) => tracing::warn!("Failed to update category of {}: {:?}", item.id, e),
+            }
+        }
+        let mut previous_tags = stored_tags.remove(&item.id).unwrap_or_default();
+        previous_tags.sort();
+        if tags != previous_tags {
+            match crud::replace_rule_tags(pool, &item.id, &tags).await {
+                Ok(()) => retagged += 1,
+                Err(e) => tracing::warn!("Failed to update tags of {}: {:?}", item.id, e),
+            }
+        }
+    }
+    tracing::info!(
+        items = total,
+        changed,
+        retagged,
+        "Recategorized statement items"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+
+    fn item(description: &str, mcc: u32, amount: i64) -> models::StatementItem {
+        models::StatementItem {
+            description: description.to_string(),
+            mcc,
+            original_mcc: mcc,
+            category: None,
+            ..testing::statement_item("item", "account", testing::at(1, 12), amount)
+        }
+    }
+
+    fn categorizer(rules: &str) -> Categorizer {
+        let mut categorizer = Categorizer::from_toml(
+            r#"
+            [[mcc]]
+            category = "groceries"
+            codes = ["5411"]
+
+            [[mcc]]
+            category = "cafes"
+            codes = ["5811-5814"]
+            "#,
+        )
+        .unwrap();
+        categorizer.load_rules(rules).unwrap();
+        categorizer
+    }
+
+    #[test]
+    fn mcc_codes_and_ranges_map_to_categories() {
+        let categorizer = categorizer("");
+        assert_eq!(categorizer.category_for_mcc(5411), Some("groceries"));
+        assert_eq!(categorizer.category_for_mcc(5812), Some("cafes"));
+        assert_eq!(categorizer.category_for_mcc(5815), None);
+    }
+
+    #[test]
+    fn higher_priority_rules_win_over_file_order() {
+        let categorizer = categorizer(
+            r#"
+            [[rule]]
+            category = "shopping"
+            description = "Silpo"
+
+            [[rule]]
+            priority = 10
+            category = "household"
+            description = "Silpo"
+            amount_max = -100000
+            "#,
+        );
+        let mut big = item("Silpo", 5411, -150_000);
+        categorizer.categorize(&mut big);
+        assert_eq!(big.category.as_deref(), Some("household"));
+
+        let mut small = item("Silpo", 5411, -5_000);
+        categorizer.categorize(&mut small);
+        assert_eq!(small.category.as_deref(), Some("shopping"));
+    }
+
+    #[test]
+    fn equal_priorities_keep_file_order() {
+        let categorizer = categorizer(
+            r#"
+            [[rule]]
+            category = "first"
+            mcc = ["5411"]
+
+            [[rule]]
+            category = "second"
+            mcc = ["5400-5499"]
+            "#,
+        );
+        let mut item = item("Silpo", 5411, -100);
+        categorizer.categorize(&mut item);
+        assert_eq!(item.category.as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn tag_only_rules_keep_the_mcc_category_and_tags_are_merged() {
+        let categorizer = categorizer(
+            r#"
+            [[rule]]
+            priority = 5
+            tags = ["daily", "coffee"]
+            description = "(?i)coffee"
+
+            [[rule]]
+            tags = ["coffee", "work"]
+            mcc = ["5814"]
+            "#,
+        );
+        let mut item = item("Coffee Point", 5814, -6_000);
+        let tags = categorizer.categorize(&mut item);
+        assert_eq!(item.category.as_deref(), Some("cafes"));
+        assert_eq!(tags, ["coffee", "daily", "work"]);
+    }
+
+    #[test]
+    fn rules_without_an_outcome_are_rejected() {
+        let mut categorizer = Categorizer::default();
+        let error = categorizer
+            .load_rules("[[rule]]\nname = \"empty\"\nmcc = [\"5411\"]")
+            .unwrap_err();
+        assert_eq!(error, "rule empty sets neither a category nor tags");
+    }
+}
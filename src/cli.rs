@@ -0,0 +1,336 @@
+use crate::export::csv::{AmountFormat, Column};
+use crate::schedule::Schedule;
+use crate::{config, export, utils};
+use clap::{Args, Parser, Subcommand};
+use serde_with::chrono::{Days, NaiveDate};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[command(version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Sync every configured token (the default when no command is given)
+    Sync(SyncArgs),
+    /// Keep running and sync on a schedule; SIGHUP reloads .env, SIGTERM stops after the current window
+    Daemon {
+        /// Interval like `6h` or cron expression like `0 3 * * *`, overrides SYNC_SCHEDULE
+        #[arg(long)]
+        schedule: Option<Schedule>,
+    },
+    /// Receive transactions through Monobank's webhook, polling on a schedule as a safety net
+    Serve {
+        /// Public URL Monobank should push to, overrides WEBHOOK_URL
+        #[arg(long)]
+        url: Option<String>,
+        /// Address to listen on, overrides WEBHOOK_LISTEN_ADDR
+        #[arg(long)]
+        listen: Option<SocketAddr>,
+        /// Polling schedule, overrides SYNC_SCHEDULE
+        #[arg(long)]
+        schedule: Option<Schedule>,
+    },
+    /// Manage the webhook Monobank pushes transactions to, for every configured token
+    Webhook {
+        #[command(subcommand)]
+        action: WebhookAction,
+    },
+    /// Add notes and tags to statement items, kept apart from synced data so syncing never overwrites them
+    Annotate {
+        #[command(subcommand)]
+        action: AnnotateAction,
+    },
+    /// Re-apply the current categories and rules to stored statement items
+    Recategorize {
+        /// Account to recategorize, every account when omitted
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Pair transfers between own accounts in already stored history; a sync does this for new items
+    ///
+    /// Jars are synced only when ALLOWED_CARD_TYPES lists `jar`, otherwise transfers between
+    /// a card and a jar stay unpaired and count as spending or income.
+    MatchTransfers {
+        /// Only look at items from this day on, the whole history when omitted
+        #[arg(long)]
+        since: Option<NaiveDate>,
+    },
+    /// Recompute every statement item's amount in BASE_CURRENCY, e.g. after adding older rates
+    RecomputeBase,
+    /// Check that every account's balances add up from item to item and match the account balance, exiting with 1 if not
+    Verify {
+        /// Account to verify, every account when omitted
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Write stored statement items to a file for other tools
+    Export {
+        #[command(subcommand)]
+        format: ExportFormat,
+    },
+    /// Re-fetch already synced history without moving the sync cursor
+    Backfill {
+        /// Account to backfill, every known account when omitted
+        #[arg(long)]
+        account: Option<String>,
+        /// First day to re-fetch. Without --from and --to, ranges missing from the sync log are re-fetched
+        #[arg(long)]
+        from: Option<NaiveDate>,
+        /// Last day to re-fetch, inclusive
+        #[arg(long)]
+        to: Option<NaiveDate>,
+        /// Day to look for missing ranges from, the account's first logged window when omitted
+        #[arg(long, conflicts_with_all = ["from", "to"])]
+        since: Option<NaiveDate>,
+    },
+    /// Move an account's sync cursor back so the next sync re-fetches from there
+    Reset {
+        /// Account to reset
+        #[arg(long)]
+        account: String,
+        /// Day to re-fetch from, SYNC_START_TIMESTAMP when omitted
+        #[arg(long)]
+        since: Option<NaiveDate>,
+        /// Delete the account's stored statement items from that day on first
+        #[arg(long)]
+        delete_items: bool,
+        /// Move the cursor even if that is not back, skipping the items in between
+        #[arg(long)]
+        force: bool,
+        /// Only print what would be re-fetched and how long it would take
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum WebhookAction {
+    /// Register the webhook URL
+    Set {
+        /// URL to register, WEBHOOK_URL when omitted
+        #[arg(long)]
+        url: Option<String>,
+    },
+    /// Show the registered webhook URL
+    Show,
+    /// Unregister the webhook
+    Clear,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum AnnotateAction {
+    /// Set the note of a statement item, replacing an earlier one
+    Note { item_id: String, note: String },
+    /// Remove the note of a statement item
+    ClearNote { item_id: String },
+    /// Add tags to a statement item
+    Tag {
+        item_id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Remove tags from a statement item
+    Untag {
+        item_id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+    /// Show the note and tags of a statement item
+    Show { item_id: String },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ExportFormat {
+    /// Comma separated values for spreadsheets
+    Csv(CsvArgs),
+    /// OFX 2 statements for GnuCash, Moneydance and other desktop apps
+    Ofx(ExportSelection),
+    /// QIF for apps that don't read OFX
+    Qif(ExportSelection),
+    /// CSV for Firefly III's data importer
+    Firefly(ExportSelection),
+    /// CSV for Actual Budget, one account at a time
+    Actual(ExportSelection),
+    /// CSV for YNAB's file import, one account at a time
+    Ynab(ExportSelection),
+    /// Journal for ledger and hledger, with balance assertions
+    Ledger(JournalArgs),
+    /// Journal for beancount, with balance assertions
+    Beancount(JournalArgs),
+    /// Statement items missing from a Parquet dataset, e.g. history stored before PARQUET_OUTPUT_DIR was set
+    #[cfg(feature = "parquet")]
+    Parquet {
+        /// Dataset directory, PARQUET_OUTPUT_DIR when omitted
+        #[arg(long)]
+        dir: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Args)]
+pub struct ExportSelection {
+    /// Account to export, repeat for several; every account when omitted
+    #[arg(long = "account")]
+    pub accounts: Vec<String>,
+    /// First day to export
+    #[arg(long)]
+    pub from: Option<NaiveDate>,
+    /// Last day to export, inclusive
+    #[arg(long)]
+    pub to: Option<NaiveDate>,
+    /// File to write, stdout when omitted (logs then go to stderr)
+    #[arg(long)]
+    pub output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+pub struct CsvArgs {
+    #[command(flatten)]
+    pub selection: ExportSelection,
+    /// Comma separated columns, in order
+    #[arg(long, value_delimiter = ',', default_value = Column::DEFAULT)]
+    pub columns: Vec<Column>,
+    /// `decimal` like -123.45 or `minor` units like -12345
+    #[arg(long, default_value = "decimal")]
+    pub amounts: AmountFormat,
+    /// Decimal separator of decimal amounts, e.g. `,` for Ukrainian spreadsheets
+    #[arg(long, default_value_t = '.')]
+    pub decimal_separator: char,
+    /// Thousands separator of decimal amounts, none when omitted
+    #[arg(long)]
+    pub thousands_separator: Option<char>,
+    /// Field delimiter, e.g. `;` when the decimal separator is `,`
+    #[arg(long, default_value = ",", value_parser = parse_delimiter)]
+    pub delimiter: u8,
+}
+
+/// A single ASCII character, as the CSV writer takes its delimiter as a byte.
+fn parse_delimiter(value: &str) -> Result<u8, String> {
+    match value.as_bytes() {
+        [byte] if byte.is_ascii() => Ok(*byte),
+        _ => Err(format!("'{value}' is not a single ASCII character")),
+    }
+}
+
+#[derive(Debug, Args)]
+pub struct JournalArgs {
+    #[command(flatten)]
+    pub selection: ExportSelection,
+    /// TOML file naming journal accounts for Monobank accounts and categories, overrides JOURNAL_ACCOUNTS_FILE
+    #[arg(long)]
+    pub accounts_file: Option<String>,
+    /// Only write items that were not written to the same output before, appending to it
+    #[arg(long)]
+    pub incremental: bool,
+}
+
+#[derive(Debug, Default, Args)]
+pub struct SyncArgs {
+    /// Plan the sync without writing anything to the database
+    #[arg(long)]
+    pub dry_run: bool,
+    /// With --dry-run, also fetch the planned windows to count new statement items
+    #[arg(long, requires = "dry_run")]
+    pub fetch: bool,
+}
+
+impl Command {
+    /// Commands that sync or move the sync cursors, which take the instance lock so two of
+    /// them never work on the same accounts. Everything else runs alongside them.
+    pub fn takes_sync_lock(&self) -> bool {
+        matches!(
+            self,
+            Command::Sync(SyncArgs { dry_run: false, .. })
+                | Command::Daemon { .. }
+                | Command::Serve { .. }
+                | Command::Backfill { .. }
+                | Command::Reset { dry_run: false, .. }
+        )
+    }
+
+    /// Commands that store or update statement items, and need `CATEGORIES_FILE` and
+    /// `RULES_FILE` to categorize them.
+    pub fn categorizes_items(&self) -> bool {
+        matches!(
+            self,
+            Command::Sync(SyncArgs { dry_run: false, .. })
+                | Command::Daemon { .. }
+                | Command::Serve { .. }
+                | Command::Backfill { .. }
+                | Command::Recategorize { .. }
+        )
+    }
+
+    /// Commands that sync, so newly stored accounts and items go to `NDJSON_OUTPUT` and
+    /// `PARQUET_OUTPUT_DIR` too, the latter after every run.
+    pub fn writes_to_sinks(&self) -> bool {
+        matches!(
+            self,
+            Command::Sync(SyncArgs { dry_run: false, .. })
+                | Command::Daemon { .. }
+                | Command::Serve { .. }
+                | Command::Backfill { .. }
+        )
+    }
+
+    /// Long-running commands, which finish the window they are on when asked to stop.
+    /// The others keep the default signal handling and exit right away.
+    pub fn stops_gracefully(&self) -> bool {
+        matches!(
+            self,
+            Command::Sync(_)
+                | Command::Daemon { .. }
+                | Command::Serve { .. }
+                | Command::Backfill { .. }
+        )
+    }
+
+    /// Whether the command writes its result to stdout, where logs would get in the way.
+    pub fn writes_to_stdout(&self) -> bool {
+        match self {
+            Command::Export { format } => format
+                .selection()
+                .is_some_and(|selection| selection.output.is_none()),
+            Command::Sync(_)
+            | Command::Daemon { .. }
+            | Command::Serve { .. }
+            | Command::Backfill { .. } => config::get_ndjson_output().as_deref() == Some("-"),
+            _ => false,
+        }
+    }
+}
+
+impl ExportFormat {
+    /// What a file export writes, `None` for datasets that pick their items themselves.
+    pub fn selection(&self) -> Option<&ExportSelection> {
+        match self {
+            ExportFormat::Csv(args) => Some(&args.selection),
+            ExportFormat::Ofx(selection)
+            | ExportFormat::Qif(selection)
+            | ExportFormat::Firefly(selection)
+            | ExportFormat::Actual(selection)
+            | ExportFormat::Ynab(selection) => Some(selection),
+            ExportFormat::Ledger(args) | ExportFormat::Beancount(args) => Some(&args.selection),
+            #[cfg(feature = "parquet")]
+            ExportFormat::Parquet { .. } => None,
+        }
+    }
+}
+
+impl ExportSelection {
+    pub fn to_selection(&self) -> export::Selection {
+        export::Selection {
+            accounts: self.accounts.clone(),
+            from: self
+                .from
+                .map(|day| utils::datetime_from(utils::day_start_timestamp(day))),
+            to: self.to.map(|day| {
+                utils::datetime_from(utils::day_start_timestamp(day + Days::new(1)) - 1)
+            }),
+        }
+    }
+}
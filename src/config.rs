@@ -1,12 +1,29 @@
+use crate::money;
+use crate::schedule::Schedule;
 use chrono_tz::Tz;
 use dotenv::dotenv;
 use serde_with::chrono::{Datelike, TimeZone, Utc};
 use std::env;
+use std::time::Duration;
 
 pub fn load_env() {
     dotenv().ok();
 }
 
+/// Re-reads `.env` over the current environment, unlike `load_env` which never overrides.
+pub fn reload_env() {
+    // Deprecated, but the only way dotenv offers to override variables that are already set
+    #[allow(deprecated)]
+    match dotenv::dotenv_iter() {
+        Ok(vars) => {
+            for (key, value) in vars.flatten() {
+                env::set_var(key, value);
+            }
+        }
+        Err(e) => tracing::warn!("Failed to reload .env: {:?}", e),
+    }
+}
+
 pub fn get_timezone() -> Tz {
     let tz_str = env::var("TIMEZONE").unwrap_or_else(|_| "Europe/Kyiv".to_string());
     tz_str.parse().unwrap_or_else(|_| {
@@ -33,6 +50,38 @@ pub fn get_database_url() -> String {
     env::var("DATABASE_URL").expect("DATABASE_URL must be set")
 }
 
+#[cfg(feature = "parquet")]
+pub fn get_parquet_output_dir() -> Option<String> {
+    env::var("PARQUET_OUTPUT_DIR").ok()
+}
+
+/// File to append newly synced accounts and statement items to as NDJSON, `-` for stdout.
+pub fn get_ndjson_output() -> Option<String> {
+    env::var("NDJSON_OUTPUT")
+        .ok()
+        .filter(|target| !target.is_empty())
+}
+
+/// Whether synced statement items only go to `NDJSON_OUTPUT`, without being stored.
+pub fn get_ndjson_only() -> bool {
+    env::var("NDJSON_ONLY")
+        .map(|value| matches!(value.trim(), "1" | "true" | "yes"))
+        .unwrap_or(false)
+}
+
+pub fn get_sync_currency_rates() -> bool {
+    env::var("SYNC_CURRENCY_RATES")
+        .map(|value| !matches!(value.trim(), "0" | "false" | "no"))
+        .unwrap_or(true)
+}
+
+/// ISO 4217 numeric code `amount_base` is kept in, configured as `UAH` or `980`.
+pub fn get_base_currency() -> u32 {
+    env::var("BASE_CURRENCY")
+        .map(|raw| money::parse_currency_code(&raw).expect("BASE_CURRENCY must be a currency code"))
+        .unwrap_or(980)
+}
+
 pub fn get_sync_start_timestamp() -> u32 {
     let raw_timestamp = env::var("SYNC_START_TIMESTAMP");
     match raw_timestamp {
@@ -48,3 +97,57 @@ pub fn get_sync_start_timestamp() -> u32 {
             .expect("SYNC_START_TIMESTAMP must be a number"),
     }
 }
+
+pub fn get_categories_file() -> Option<String> {
+    env::var("CATEGORIES_FILE").ok()
+}
+
+pub fn get_journal_accounts_file() -> Option<String> {
+    env::var("JOURNAL_ACCOUNTS_FILE").ok()
+}
+
+pub fn get_rules_file() -> Option<String> {
+    env::var("RULES_FILE").ok()
+}
+
+/// How far apart the two sides of a transfer between own accounts may be booked.
+pub fn get_transfer_match_window() -> Duration {
+    let raw = env::var("TRANSFER_MATCH_WINDOW").unwrap_or_else(|_| "10m".to_string());
+    humantime::parse_duration(&raw).unwrap_or_else(|_| {
+        tracing::warn!(
+            "Invalid TRANSFER_MATCH_WINDOW '{}', falling back to 10m",
+            raw
+        );
+        Duration::from_secs(600)
+    })
+}
+
+/// Descriptions Monobank gives transfers between own cards, like "З гривневого рахунку".
+pub fn get_transfer_description_pattern() -> String {
+    env::var("TRANSFER_DESCRIPTION_PATTERN")
+        .unwrap_or_else(|_| "(?i)(рахун|картк|банк)".to_string())
+}
+
+pub fn get_webhook_url() -> Option<String> {
+    env::var("WEBHOOK_URL").ok()
+}
+
+pub fn get_webhook_listen_addr() -> String {
+    env::var("WEBHOOK_LISTEN_ADDR").unwrap_or_else(|_| "0.0.0.0:8080".to_string())
+}
+
+pub fn get_sync_schedule() -> Result<Schedule, String> {
+    env::var("SYNC_SCHEDULE")
+        .unwrap_or_else(|_| "24h".to_string())
+        .parse()
+}
+
+/// How long an SQLite lock row may go without a heartbeat before another instance takes it over.
+#[cfg(feature = "sqlite")]
+pub fn get_lock_stale_after() -> Duration {
+    let raw = env::var("LOCK_STALE_AFTER").unwrap_or_else(|_| "10m".to_string());
+    humantime::parse_duration(&raw).unwrap_or_else(|_| {
+        tracing::warn!("Invalid LOCK_STALE_AFTER '{}', falling back to 10m", raw);
+        Duration::from_secs(600)
+    })
+}
@@ -0,0 +1,208 @@
+use crate::db_types::DatabasePool;
+use crate::{crud, models, money, utils};
+use serde_with::chrono::NaiveDateTime;
+use std::collections::HashMap;
+
+/// Monobank quotes most currencies against the hryvnia only, so other pairs go through it.
+const UAH: u32 = 980;
+
+/// Stored exchange rates by pair, each series sorted by date.
+pub struct RateTable {
+    pairs: HashMap<(u32, u32), Vec<(NaiveDateTime, f64)>>,
+}
+
+impl RateTable {
+    pub fn new(rates: Vec<models::CurrencyRate>) -> Self {
+        let mut pairs: HashMap<(u32, u32), Vec<(NaiveDateTime, f64)>> = HashMap::new();
+        for rate in rates {
+            let value = match (rate.rate_buy, rate.rate_sell, rate.rate_cross) {
+                (Some(buy), Some(sell), _) => (buy + sell) / 2.0,
+                (_, _, Some(cross)) => cross,
+                _ => continue,
+            };
+            pairs
+                .entry((rate.currency_code_a, rate.currency_code_b))
+                .or_default()
+                .push((rate.date, value));
+        }
+        for series in pairs.values_mut() {
+            series.sort_by_key(|(date, _)| *date);
+        }
+        Self { pairs }
+    }
+
+    /// Units of `to` per unit of `from` as of `at`, going through the hryvnia when
+    /// there is no direct pair.
+    pub fn rate(&self, from: u32, to: u32, at: NaiveDateTime) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        self.pair_rate(from, to, at)
+            .or_else(|| Some(self.pair_rate(from, UAH, at)? * self.pair_rate(UAH, to, at)?))
+    }
+
+    fn pair_rate(&self, from: u32, to: u32, at: NaiveDateTime) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        if let Some(series) = self.pairs.get(&(from, to)) {
+            return rate_as_of(series, at);
+        }
+        let inverse = rate_as_of(self.pairs.get(&(to, from))?, at)?;
+        Some(1.0 / inverse)
+    }
+}
+
+/// The last rate known at `at`, or the first one after it for items older than the history.
+fn rate_as_of(series: &[(NaiveDateTime, f64)], at: NaiveDateTime) -> Option<f64> {
+    let known = series.partition_point(|(date, _)| *date <= at);
+    let index = known.saturating_sub(1);
+    series.get(index).map(|(_, value)| *value)
+}
+
+/// Converts minor units of `from` into minor units of `to`, which may have a different number of digits.
+pub fn convert(amount: i64, from: u32, to: u32, rate: f64) -> i64 {
+    let digits = money::minor_units(to) as i32 - money::minor_units(from) as i32;
+    (amount as f64 * rate * 10f64.powi(digits)).round() as i64
+}
+
+/// Fills `amount_base` in `base_currency` for items that miss it or have it in another
+/// currency, or for every item with `all`, e.g. after rates were backfilled. Items without
+/// a rate are only tried again once a fetch stores new rates.
+pub async fn update_base_amounts(pool: &DatabasePool, base_currency: u32, all: bool) {
+    let rates = match crud::get_currency_rates(pool).await {
+        Ok(rates) => RateTable::new(rates),
+        Err(e) => {
+            tracing::error!("Failed to get currency rates: {:?}", e);
+            return;
+        }
+    };
+    let sources = match crud::get_base_amount_sources(pool, base_currency, all).await {
+        Ok(sources) => sources,
+        Err(e) => {
+            tracing::error!("Failed to get statement items to convert: {:?}", e);
+            return;
+        }
+    };
+    let attempted_at = utils::datetime_from(utils::current_timestamp());
+    let mut converted = 0;
+    let mut without_rate = 0;
+    for source in &sources {
+        let amount_base = rates
+            .rate(source.account_currency as u32, base_currency, source.time)
+            .map(|rate| {
+                convert(
+                    source.amount,
+                    source.account_currency as u32,
+                    base_currency,
+                    rate,
+                )
+            });
+        match amount_base {
+            Some(_) => converted += 1,
+            None => without_rate += 1,
+        }
+        if let Err(e) =
+            crud::update_amount_base(pool, &source.id, amount_base, base_currency, attempted_at)
+                .await
+        {
+            tracing::warn!("Failed to update base amount of {}: {:?}", source.id, e);
+        }
+    }
+    if !sources.is_empty() {
+        tracing::info!(
+            base_currency,
+            converted,
+            without_rate,
+            "Base currency amounts updated"
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_with::chrono::NaiveDate;
+
+    const USD: u32 = 840;
+    const EUR: u32 = 978;
+    const JPY: u32 = 392;
+    const KWD: u32 = 414;
+
+    fn day(day: u32) -> NaiveDateTime {
+        NaiveDate::from_ymd_opt(2026, 10, day)
+            .unwrap()
+            .and_hms_opt(0, 0, 0)
+            .unwrap()
+    }
+
+    fn rate(
+        a: u32,
+        b: u32,
+        date: u32,
+        buy_sell: Option<(f64, f64)>,
+        cross: Option<f64>,
+    ) -> models::CurrencyRate {
+        models::CurrencyRate {
+            currency_code_a: a,
+            currency_code_b: b,
+            date: day(date),
+            rate_buy: buy_sell.map(|(buy, _)| buy),
+            rate_sell: buy_sell.map(|(_, sell)| sell),
+            rate_cross: cross,
+            fetched_at: day(date),
+        }
+    }
+
+    fn table() -> RateTable {
+        RateTable::new(vec![
+            rate(USD, UAH, 10, Some((41.0, 42.0)), None),
+            rate(USD, UAH, 5, Some((39.0, 40.0)), None),
+            rate(EUR, UAH, 5, None, Some(45.0)),
+            rate(JPY, UAH, 5, None, None),
+        ])
+    }
+
+    fn assert_close(actual: Option<f64>, expected: f64) {
+        let actual = actual.unwrap();
+        assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn buy_and_sell_are_averaged_as_of_the_date() {
+        let rates = table();
+        assert_close(rates.rate(USD, UAH, day(7)), 39.5);
+        assert_close(rates.rate(USD, UAH, day(10)), 41.5);
+        assert_close(rates.rate(USD, UAH, day(20)), 41.5);
+        // Items older than the history take its first rate
+        assert_close(rates.rate(USD, UAH, day(1)), 39.5);
+    }
+
+    #[test]
+    fn inverse_pairs_are_divided() {
+        assert_close(table().rate(UAH, EUR, day(7)), 1.0 / 45.0);
+    }
+
+    #[test]
+    fn other_pairs_cross_through_the_hryvnia() {
+        assert_close(table().rate(EUR, USD, day(7)), 45.0 / 39.5);
+    }
+
+    #[test]
+    fn missing_rates_are_none() {
+        let rates = table();
+        assert_eq!(rates.rate(JPY, UAH, day(7)), None);
+        assert_eq!(rates.rate(KWD, USD, day(7)), None);
+        assert_eq!(rates.rate(KWD, KWD, day(7)), Some(1.0));
+    }
+
+    #[test]
+    fn conversion_scales_minor_units() {
+        assert_eq!(convert(1_050, USD, UAH, 41.5), 43_575);
+        // Yen have no minor units and dinars three
+        assert_eq!(convert(1_000, JPY, UAH, 0.28), 28_000);
+        assert_eq!(convert(10_000, UAH, JPY, 3.5), 350);
+        assert_eq!(convert(1_000, KWD, USD, 3.25), 325);
+        assert_eq!(convert(325, USD, KWD, 1.0 / 3.25), 1_000);
+    }
+}
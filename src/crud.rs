@@ -2,6 +2,18 @@ use crate::db_types::DatabasePool;
 use crate::models;
 use serde_with::chrono::NaiveDateTime;
 
+/// MySQL has no `ON CONFLICT DO NOTHING`, and `INSERT IGNORE` would also turn truncated
+/// values and NOT NULL violations into warnings, so only duplicate keys are let through here.
+#[cfg(feature = "mysql")]
+fn skip_duplicate(
+    result: Result<sqlx::mysql::MySqlQueryResult, sqlx::Error>,
+) -> Result<sqlx::mysql::MySqlQueryResult, sqlx::Error> {
+    match result {
+        Err(sqlx::Error::Database(e)) if e.is_unique_violation() => Ok(Default::default()),
+        result => result,
+    }
+}
+
 pub async fn insert_client_info(
     pool: &DatabasePool,
     client_info: models::ClientInfo,
@@ -28,9 +40,59 @@ pub async fn insert_client_info(
     .execute(pool)
     .await;
 
+    #[cfg(feature = "mysql")]
+    let result = skip_duplicate(
+        sqlx::query!(
+            "INSERT INTO client_info (client_id, name, token) VALUES (?, ?, ?)",
+            client_info.client_id,
+            client_info.name,
+            client_info.token,
+        )
+        .execute(pool)
+        .await,
+    );
+
     result.map(|_| ())
 }
 
+/// Records the webhook Monobank pushes the token's transactions to, `None` when there is none.
+pub async fn update_webhook_url(
+    pool: &DatabasePool,
+    token: &str,
+    webhook_url: Option<&str>,
+) -> Result<u64, sqlx::Error> {
+    tracing::debug!("Updating client webhook...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "UPDATE client_info SET webhook_url = ? WHERE token = ?",
+        webhook_url,
+        token,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "UPDATE client_info SET webhook_url = $1 WHERE token = $2",
+        webhook_url,
+        token,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "UPDATE client_info SET webhook_url = ? WHERE token = ?",
+        webhook_url,
+        token,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|r| r.rows_affected())
+}
+
 pub async fn update_last_sync_time(
     pool: &DatabasePool,
     account_id: String,
@@ -56,6 +118,15 @@ pub async fn update_last_sync_time(
     .execute(pool)
     .await;
 
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "UPDATE accounts SET last_sync_at = ? WHERE id = ?",
+        last_sync_at,
+        account_id,
+    )
+    .execute(pool)
+    .await;
+
     result.map(|_| ())
 }
 
@@ -83,6 +154,15 @@ pub async fn get_last_sync_time(
     .fetch_optional(pool)
     .await;
 
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::LastSync,
+        r#"SELECT last_sync_at as "last_sync_at: _" FROM accounts WHERE id = ?"#,
+        account_id
+    )
+    .fetch_optional(pool)
+    .await;
+
     match result {
         Ok(Some(time_struct)) => Ok(time_struct.last_sync_at),
         Ok(None) => Ok(None),
@@ -130,18 +210,78 @@ pub async fn insert_account(
     .execute(pool)
     .await;
 
+    #[cfg(feature = "mysql")]
+    let result = skip_duplicate(sqlx::query!(
+        "INSERT INTO accounts (id, client_id, send_id, balance, credit_limit, account_type, currency_code, cashback_type, iban, last_sync_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        account.id,
+        account.client_id,
+        account.send_id,
+        account.balance,
+        account.credit_limit,
+        account.account_type,
+        account.currency_code,
+        account.cashback_type,
+        account.iban,
+        account.last_sync_at,
+    )
+    .execute(pool)
+    .await);
+
+    result.map(|_| ())
+}
+
+/// Refreshes the balance `insert_account` leaves as it was on the first sync.
+pub async fn update_account_balance(
+    pool: &DatabasePool,
+    id: &str,
+    balance: i64,
+    credit_limit: i64,
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Updating account balance...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "UPDATE accounts SET balance = ?, credit_limit = ? WHERE id = ?",
+        balance,
+        credit_limit,
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "UPDATE accounts SET balance = $1, credit_limit = $2 WHERE id = $3",
+        balance,
+        credit_limit,
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "UPDATE accounts SET balance = ?, credit_limit = ? WHERE id = ?",
+        balance,
+        credit_limit,
+        id,
+    )
+    .execute(pool)
+    .await;
+
     result.map(|_| ())
 }
 
+/// Returns whether the item was new, existing ids are left untouched.
 pub async fn insert_statement_item(
     pool: &DatabasePool,
     statement_item: models::StatementItem,
-) -> Result<(), sqlx::Error> {
+) -> Result<bool, sqlx::Error> {
     tracing::debug!("Trying to write statement item...");
 
     #[cfg(feature = "sqlite")]
     let result = sqlx::query!(
-        "INSERT OR IGNORE INTO statement_items (id, account_id, time, description, mcc, original_mcc, hold, amount, operation_amount, currency_code, commission_rate, cashback_amount, balance, comment, receipt_id, invoice_id, counter_edrpou, counter_iban, counter_name) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        "INSERT OR IGNORE INTO statement_items (id, account_id, time, description, mcc, original_mcc, hold, amount, operation_amount, currency_code, commission_rate, cashback_amount, balance, comment, receipt_id, invoice_id, counter_edrpou, counter_iban, counter_name, category) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
         statement_item.id,
         statement_item.account_id,
         statement_item.time,
@@ -161,13 +301,14 @@ pub async fn insert_statement_item(
         statement_item.counter_edrpou,
         statement_item.counter_iban,
         statement_item.counter_name,
+        statement_item.category,
     )
     .execute(pool)
     .await;
 
     #[cfg(feature = "postgres")]
     let result = sqlx::query!(
-        "INSERT INTO statement_items (id, account_id, time, description, mcc, original_mcc, hold, amount, operation_amount, currency_code, commission_rate, cashback_amount, balance, comment, receipt_id, invoice_id, counter_edrpou, counter_iban, counter_name) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19) ON CONFLICT (id) DO NOTHING",
+        "INSERT INTO statement_items (id, account_id, time, description, mcc, original_mcc, hold, amount, operation_amount, currency_code, commission_rate, cashback_amount, balance, comment, receipt_id, invoice_id, counter_edrpou, counter_iban, counter_name, category) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20) ON CONFLICT (id) DO NOTHING",
         statement_item.id,
         statement_item.account_id,
         statement_item.time,
@@ -187,9 +328,1302 @@ pub async fn insert_statement_item(
         statement_item.counter_edrpou,
         statement_item.counter_iban,
         statement_item.counter_name,
+        statement_item.category,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = skip_duplicate(sqlx::query!(
+        "INSERT INTO statement_items (id, account_id, time, description, mcc, original_mcc, hold, amount, operation_amount, currency_code, commission_rate, cashback_amount, balance, comment, receipt_id, invoice_id, counter_edrpou, counter_iban, counter_name, category) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        statement_item.id,
+        statement_item.account_id,
+        statement_item.time,
+        statement_item.description,
+        statement_item.mcc,
+        statement_item.original_mcc,
+        statement_item.hold,
+        statement_item.amount,
+        statement_item.operation_amount,
+        statement_item.currency_code,
+        statement_item.commission_rate,
+        statement_item.cashback_amount,
+        statement_item.balance,
+        statement_item.comment,
+        statement_item.receipt_id,
+        statement_item.invoice_id,
+        statement_item.counter_edrpou,
+        statement_item.counter_iban,
+        statement_item.counter_name,
+        statement_item.category,
+    )
+    .execute(pool)
+    .await);
+
+    result.map(|r| r.rows_affected() > 0)
+}
+
+pub async fn start_sync_run(
+    pool: &DatabasePool,
+    token_fingerprint: &str,
+    started_at: NaiveDateTime,
+) -> Result<i64, sqlx::Error> {
+    tracing::debug!("Recording sync run start...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_scalar!(
+        "INSERT INTO sync_runs (token_fingerprint, started_at) VALUES (?, ?) RETURNING id",
+        token_fingerprint,
+        started_at,
+    )
+    .fetch_one(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_scalar!(
+        "INSERT INTO sync_runs (token_fingerprint, started_at) VALUES ($1, $2) RETURNING id",
+        token_fingerprint,
+        started_at,
+    )
+    .fetch_one(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "INSERT INTO sync_runs (token_fingerprint, started_at) VALUES (?, ?)",
+        token_fingerprint,
+        started_at,
+    )
+    .execute(pool)
+    .await
+    .map(|r| r.last_insert_id() as i64);
+
+    result
+}
+
+pub async fn finish_sync_run(
+    pool: &DatabasePool,
+    run: &models::SyncRun,
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Recording sync run result...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "UPDATE sync_runs SET finished_at = ?, accounts_touched = ?, windows_fetched = ?, items_fetched = ?, items_inserted = ?, errors = ? WHERE id = ?",
+        run.finished_at,
+        run.accounts_touched,
+        run.windows_fetched,
+        run.items_fetched,
+        run.items_inserted,
+        run.errors,
+        run.id,
     )
     .execute(pool)
     .await;
 
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "UPDATE sync_runs SET finished_at = $1, accounts_touched = $2, windows_fetched = $3, items_fetched = $4, items_inserted = $5, errors = $6 WHERE id = $7",
+        run.finished_at,
+        run.accounts_touched,
+        run.windows_fetched,
+        run.items_fetched,
+        run.items_inserted,
+        run.errors,
+        run.id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "UPDATE sync_runs SET finished_at = ?, accounts_touched = ?, windows_fetched = ?, items_fetched = ?, items_inserted = ?, errors = ? WHERE id = ?",
+        run.finished_at,
+        run.accounts_touched,
+        run.windows_fetched,
+        run.items_fetched,
+        run.items_inserted,
+        run.errors,
+        run.id,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|_| ())
+}
+
+pub async fn insert_sync_window(
+    pool: &DatabasePool,
+    window: models::SyncWindow,
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Recording sync window...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "INSERT INTO sync_windows (run_id, account_id, window_start, window_end, status, items_fetched, items_inserted, error, fetched_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        window.run_id,
+        window.account_id,
+        window.window_start,
+        window.window_end,
+        window.status,
+        window.items_fetched,
+        window.items_inserted,
+        window.error,
+        window.fetched_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "INSERT INTO sync_windows (run_id, account_id, window_start, window_end, status, items_fetched, items_inserted, error, fetched_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9)",
+        window.run_id,
+        window.account_id,
+        window.window_start,
+        window.window_end,
+        window.status,
+        window.items_fetched,
+        window.items_inserted,
+        window.error,
+        window.fetched_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "INSERT INTO sync_windows (run_id, account_id, window_start, window_end, status, items_fetched, items_inserted, error, fetched_at) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?)",
+        window.run_id,
+        window.account_id,
+        window.window_start,
+        window.window_end,
+        window.status,
+        window.items_fetched,
+        window.items_inserted,
+        window.error,
+        window.fetched_at,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|_| ())
+}
+
+pub async fn get_account_ids(pool: &DatabasePool) -> Result<Vec<String>, sqlx::Error> {
+    tracing::debug!("Retrieving account ids from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_scalar!(r#"SELECT id as "id!" FROM accounts ORDER BY id"#)
+        .fetch_all(pool)
+        .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_scalar!(r#"SELECT id as "id!" FROM accounts ORDER BY id"#)
+        .fetch_all(pool)
+        .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_scalar!(r#"SELECT id as "id!" FROM accounts ORDER BY id"#)
+        .fetch_all(pool)
+        .await;
+
+    result
+}
+
+pub async fn get_account_token(
+    pool: &DatabasePool,
+    account_id: &str,
+) -> Result<Option<String>, sqlx::Error> {
+    tracing::debug!("Retrieving account token from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT c.token as "token!" FROM accounts a JOIN client_info c ON c.client_id = a.client_id WHERE a.id = ?"#,
+        account_id
+    )
+    .fetch_optional(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT c.token as "token!" FROM accounts a JOIN client_info c ON c.client_id = a.client_id WHERE a.id = $1"#,
+        account_id
+    )
+    .fetch_optional(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT c.token as "token!" FROM accounts a JOIN client_info c ON c.client_id = a.client_id WHERE a.id = ?"#,
+        account_id
+    )
+    .fetch_optional(pool)
+    .await;
+
+    result
+}
+
+pub async fn get_successful_windows(
+    pool: &DatabasePool,
+    account_id: &str,
+) -> Result<Vec<models::WindowRange>, sqlx::Error> {
+    tracing::debug!("Retrieving successful sync windows from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_as!(
+        models::WindowRange,
+        r#"SELECT window_start as "window_start: _", window_end as "window_end: _" FROM sync_windows WHERE account_id = ? AND status = 'success' ORDER BY window_start"#,
+        account_id
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_as!(
+        models::WindowRange,
+        r#"SELECT window_start as "window_start: _", window_end as "window_end: _" FROM sync_windows WHERE account_id = $1 AND status = 'success' ORDER BY window_start"#,
+        account_id
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::WindowRange,
+        r#"SELECT window_start as "window_start: _", window_end as "window_end: _" FROM sync_windows WHERE account_id = ? AND status = 'success' ORDER BY window_start"#,
+        account_id
+    )
+    .fetch_all(pool)
+    .await;
+
+    result
+}
+
+pub async fn count_statement_items_since(
+    pool: &DatabasePool,
+    account_id: &str,
+    since: NaiveDateTime,
+) -> Result<i64, sqlx::Error> {
+    tracing::debug!("Counting statement items in DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!: i64" FROM statement_items WHERE account_id = ? AND time >= ?"#,
+        account_id,
+        since,
+    )
+    .fetch_one(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!: i64" FROM statement_items WHERE account_id = $1 AND time >= $2"#,
+        account_id,
+        since,
+    )
+    .fetch_one(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!: i64" FROM statement_items WHERE account_id = ? AND time >= ?"#,
+        account_id,
+        since,
+    )
+    .fetch_one(pool)
+    .await;
+
+    result
+}
+
+pub async fn delete_statement_items_since(
+    pool: &DatabasePool,
+    account_id: &str,
+    since: NaiveDateTime,
+) -> Result<u64, sqlx::Error> {
+    tracing::debug!("Deleting statement items from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "DELETE FROM statement_items WHERE account_id = ? AND time >= ?",
+        account_id,
+        since,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "DELETE FROM statement_items WHERE account_id = $1 AND time >= $2",
+        account_id,
+        since,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "DELETE FROM statement_items WHERE account_id = ? AND time >= ?",
+        account_id,
+        since,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|r| r.rows_affected())
+}
+
+pub async fn statement_item_exists(pool: &DatabasePool, id: &str) -> Result<bool, sqlx::Error> {
+    tracing::debug!("Checking statement item in DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!: i64" FROM statement_items WHERE id = ?"#,
+        id
+    )
+    .fetch_one(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!: i64" FROM statement_items WHERE id = $1"#,
+        id
+    )
+    .fetch_one(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT COUNT(*) as "count!: i64" FROM statement_items WHERE id = ?"#,
+        id
+    )
+    .fetch_one(pool)
+    .await;
+
+    result.map(|count| count > 0)
+}
+
+pub async fn insert_currency_rate(
+    pool: &DatabasePool,
+    rate: models::CurrencyRate,
+) -> Result<bool, sqlx::Error> {
+    tracing::debug!("Trying to write currency rate...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "INSERT OR IGNORE INTO currency_rates (currency_code_a, currency_code_b, date, rate_buy, rate_sell, rate_cross, fetched_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        rate.currency_code_a,
+        rate.currency_code_b,
+        rate.date,
+        rate.rate_buy,
+        rate.rate_sell,
+        rate.rate_cross,
+        rate.fetched_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "INSERT INTO currency_rates (currency_code_a, currency_code_b, date, rate_buy, rate_sell, rate_cross, fetched_at) VALUES ($1, $2, $3, $4, $5, $6, $7) ON CONFLICT (currency_code_a, currency_code_b, date) DO NOTHING",
+        rate.currency_code_a as i32,
+        rate.currency_code_b as i32,
+        rate.date,
+        rate.rate_buy,
+        rate.rate_sell,
+        rate.rate_cross,
+        rate.fetched_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = skip_duplicate(sqlx::query!(
+        "INSERT INTO currency_rates (currency_code_a, currency_code_b, date, rate_buy, rate_sell, rate_cross, fetched_at) VALUES (?, ?, ?, ?, ?, ?, ?)",
+        rate.currency_code_a,
+        rate.currency_code_b,
+        rate.date,
+        rate.rate_buy,
+        rate.rate_sell,
+        rate.rate_cross,
+        rate.fetched_at,
+    )
+    .execute(pool)
+    .await);
+
+    result.map(|r| r.rows_affected() > 0)
+}
+
+pub async fn insert_currency_rate_fetch(
+    pool: &DatabasePool,
+    fetched_at: NaiveDateTime,
+    pairs: u32,
+    new_rates: u32,
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Trying to write currency rate fetch...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "INSERT INTO currency_rate_fetches (fetched_at, pairs, new_rates) VALUES (?, ?, ?)",
+        fetched_at,
+        pairs,
+        new_rates,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "INSERT INTO currency_rate_fetches (fetched_at, pairs, new_rates) VALUES ($1, $2, $3)",
+        fetched_at,
+        pairs as i32,
+        new_rates as i32,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "INSERT INTO currency_rate_fetches (fetched_at, pairs, new_rates) VALUES (?, ?, ?)",
+        fetched_at,
+        pairs,
+        new_rates,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|_| ())
+}
+
+pub async fn get_last_rates_fetch(
+    pool: &DatabasePool,
+) -> Result<Option<NaiveDateTime>, sqlx::Error> {
+    tracing::debug!("Retrieving last currency rates fetch from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT MAX(fetched_at) as "fetched_at: NaiveDateTime" FROM currency_rate_fetches"#
+    )
+    .fetch_one(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result =
+        sqlx::query_scalar!("SELECT MAX(fetched_at) as fetched_at FROM currency_rate_fetches")
+            .fetch_one(pool)
+            .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_scalar!(
+        r#"SELECT MAX(fetched_at) as "fetched_at: NaiveDateTime" FROM currency_rate_fetches"#
+    )
+    .fetch_one(pool)
+    .await;
+
+    result
+}
+
+pub async fn get_currency_rates(
+    pool: &DatabasePool,
+) -> Result<Vec<models::CurrencyRate>, sqlx::Error> {
+    tracing::debug!("Retrieving currency rates from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        r#"SELECT currency_code_a, currency_code_b, date as "date: NaiveDateTime", rate_buy, rate_sell, rate_cross, fetched_at as "fetched_at: NaiveDateTime" FROM currency_rates ORDER BY date"#
+    )
+    .fetch_all(pool)
+    .await
+    .map(|rows| {
+        rows.into_iter()
+            .map(|r| models::CurrencyRate {
+                currency_code_a: r.currency_code_a as u32,
+                currency_code_b: r.currency_code_b as u32,
+                date: r.date,
+                rate_buy: r.rate_buy,
+                rate_sell: r.rate_sell,
+                rate_cross: r.rate_cross,
+                fetched_at: r.fetched_at,
+            })
+            .collect()
+    });
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "SELECT currency_code_a, currency_code_b, date, rate_buy, rate_sell, rate_cross, fetched_at FROM currency_rates ORDER BY date"
+    )
+    .fetch_all(pool)
+    .await
+    .map(|rows| {
+        rows.into_iter()
+            .map(|r| models::CurrencyRate {
+                currency_code_a: r.currency_code_a as u32,
+                currency_code_b: r.currency_code_b as u32,
+                date: r.date,
+                rate_buy: r.rate_buy,
+                rate_sell: r.rate_sell,
+                rate_cross: r.rate_cross,
+                fetched_at: r.fetched_at,
+            })
+            .collect()
+    });
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        r#"SELECT currency_code_a, currency_code_b, date as "date: NaiveDateTime", rate_buy, rate_sell, rate_cross, fetched_at as "fetched_at: NaiveDateTime" FROM currency_rates ORDER BY date"#
+    )
+    .fetch_all(pool)
+    .await
+    .map(|rows| {
+        rows.into_iter()
+            .map(|r| models::CurrencyRate {
+                currency_code_a: r.currency_code_a as u32,
+                currency_code_b: r.currency_code_b as u32,
+                date: r.date,
+                rate_buy: r.rate_buy,
+                rate_sell: r.rate_sell,
+                rate_cross: r.rate_cross,
+                fetched_at: r.fetched_at,
+            })
+            .collect()
+    });
+
+    result
+}
+
+/// Items never converted or converted into another currency than `base_currency`, those that
+/// had no rate when rates arrived since, or all of them.
+pub async fn get_base_amount_sources(
+    pool: &DatabasePool,
+    base_currency: u32,
+    all: bool,
+) -> Result<Vec<models::BaseAmountSource>, sqlx::Error> {
+    tracing::debug!("Retrieving statement items to convert from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_as!(
+        models::BaseAmountSource,
+        r#"SELECT s.id as "id!", s.time as "time: NaiveDateTime", s.amount, a.currency_code as "account_currency: i64" FROM statement_items s JOIN accounts a ON a.id = s.account_id WHERE ? OR s.amount_base_attempted_at IS NULL OR s.amount_base_currency IS NULL OR s.amount_base_currency <> ? OR (s.amount_base IS NULL AND s.amount_base_attempted_at < (SELECT MAX(f.fetched_at) FROM currency_rate_fetches f WHERE f.new_rates > 0))"#,
+        all,
+        base_currency,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_as!(
+        models::BaseAmountSource,
+        r#"SELECT s.id, s.time, s.amount, a.currency_code::BIGINT as "account_currency!" FROM statement_items s JOIN accounts a ON a.id = s.account_id WHERE $1 OR s.amount_base_attempted_at IS NULL OR s.amount_base_currency IS NULL OR s.amount_base_currency <> $2 OR (s.amount_base IS NULL AND s.amount_base_attempted_at < (SELECT MAX(f.fetched_at) FROM currency_rate_fetches f WHERE f.new_rates > 0))"#,
+        all,
+        base_currency as i32,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::BaseAmountSource,
+        r#"SELECT s.id, s.time as "time: NaiveDateTime", s.amount, CAST(a.currency_code AS SIGNED) as "account_currency!: i64" FROM statement_items s JOIN accounts a ON a.id = s.account_id WHERE ? OR s.amount_base_attempted_at IS NULL OR s.amount_base_currency IS NULL OR s.amount_base_currency <> ? OR (s.amount_base IS NULL AND s.amount_base_attempted_at < (SELECT MAX(f.fetched_at) FROM currency_rate_fetches f WHERE f.new_rates > 0))"#,
+        all,
+        base_currency,
+    )
+    .fetch_all(pool)
+    .await;
+
+    result
+}
+
+pub async fn update_amount_base(
+    pool: &DatabasePool,
+    id: &str,
+    amount_base: Option<i64>,
+    base_currency: u32,
+    attempted_at: NaiveDateTime,
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Updating statement item base amount...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "UPDATE statement_items SET amount_base = ?, amount_base_currency = ?, amount_base_attempted_at = ? WHERE id = ?",
+        amount_base,
+        base_currency,
+        attempted_at,
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "UPDATE statement_items SET amount_base = $1, amount_base_currency = $2, amount_base_attempted_at = $3 WHERE id = $4",
+        amount_base,
+        base_currency as i32,
+        attempted_at,
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "UPDATE statement_items SET amount_base = ?, amount_base_currency = ?, amount_base_attempted_at = ? WHERE id = ?",
+        amount_base,
+        base_currency,
+        attempted_at,
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|_| ())
+}
+
+/// Stored items ordered by time, optionally of one account and within `from..=to`.
+pub async fn get_statement_items(
+    pool: &DatabasePool,
+    account_id: Option<&str>,
+    range: Option<(NaiveDateTime, NaiveDateTime)>,
+) -> Result<Vec<models::StatementItem>, sqlx::Error> {
+    tracing::debug!("Retrieving statement items from DB...");
+    let (from, to) = range.unzip();
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_as!(
+        models::StatementItemRow,
+        r#"SELECT id as "id!", account_id, time as "time: NaiveDateTime", description, mcc, original_mcc, hold, amount, operation_amount, currency_code, commission_rate, cashback_amount, balance, comment, receipt_id, invoice_id, counter_edrpou, counter_iban, counter_name, category FROM statement_items WHERE (? IS NULL OR account_id = ?) AND (? IS NULL OR time >= ?) AND (? IS NULL OR time <= ?) ORDER BY time, id"#,
+        account_id,
+        account_id,
+        from,
+        from,
+        to,
+        to,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_as!(
+        models::StatementItemRow,
+        r#"SELECT id, account_id, time, description, mcc::BIGINT as "mcc!", original_mcc::BIGINT as "original_mcc!", hold, amount, operation_amount, currency_code::BIGINT as "currency_code!", commission_rate, cashback_amount, balance, comment, receipt_id, invoice_id, counter_edrpou, counter_iban, counter_name, category FROM statement_items WHERE ($1::TEXT IS NULL OR account_id = $1) AND ($2::TIMESTAMP IS NULL OR time >= $2) AND ($3::TIMESTAMP IS NULL OR time <= $3) ORDER BY time, id"#,
+        account_id,
+        from,
+        to,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::StatementItemRow,
+        r#"SELECT id, account_id, time as "time: NaiveDateTime", description, CAST(mcc AS SIGNED) as "mcc!: i64", CAST(original_mcc AS SIGNED) as "original_mcc!: i64", hold as "hold: bool", amount, operation_amount, CAST(currency_code AS SIGNED) as "currency_code!: i64", commission_rate, cashback_amount, balance, comment, receipt_id, invoice_id, counter_edrpou, counter_iban, counter_name, category FROM statement_items WHERE (? IS NULL OR account_id = ?) AND (? IS NULL OR time >= ?) AND (? IS NULL OR time <= ?) ORDER BY time, id"#,
+        account_id,
+        account_id,
+        from,
+        from,
+        to,
+        to,
+    )
+    .fetch_all(pool)
+    .await;
+
+    result.map(|rows| rows.into_iter().map(models::StatementItem::from).collect())
+}
+
+pub async fn update_category(
+    pool: &DatabasePool,
+    id: &str,
+    category: Option<&str>,
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Updating statement item category...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "UPDATE statement_items SET category = ? WHERE id = ?",
+        category,
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "UPDATE statement_items SET category = $1 WHERE id = $2",
+        category,
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "UPDATE statement_items SET category = ? WHERE id = ?",
+        category,
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|_| ())
+}
+
+/// Replaces the rule tags of a statement item with `tags`.
+pub async fn replace_rule_tags(
+    pool: &DatabasePool,
+    id: &str,
+    tags: &[String],
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Replacing statement item tags...");
+    let mut tx = pool.begin().await?;
+
+    #[cfg(feature = "sqlite")]
+    sqlx::query!(
+        "DELETE FROM statement_item_tags WHERE statement_item_id = ?",
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    #[cfg(feature = "postgres")]
+    sqlx::query!(
+        "DELETE FROM statement_item_tags WHERE statement_item_id = $1",
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    #[cfg(feature = "mysql")]
+    sqlx::query!(
+        "DELETE FROM statement_item_tags WHERE statement_item_id = ?",
+        id
+    )
+    .execute(&mut *tx)
+    .await?;
+
+    for tag in tags {
+        #[cfg(feature = "sqlite")]
+        sqlx::query!(
+            "INSERT OR IGNORE INTO statement_item_tags (statement_item_id, tag) VALUES (?, ?)",
+            id,
+            tag,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        #[cfg(feature = "postgres")]
+        sqlx::query!(
+            "INSERT INTO statement_item_tags (statement_item_id, tag) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+            id,
+            tag,
+        )
+        .execute(&mut *tx)
+        .await?;
+
+        #[cfg(feature = "mysql")]
+        skip_duplicate(
+            sqlx::query!(
+                "INSERT INTO statement_item_tags (statement_item_id, tag) VALUES (?, ?)",
+                id,
+                tag,
+            )
+            .execute(&mut *tx)
+            .await,
+        )?;
+    }
+
+    tx.commit().await
+}
+
+/// Rule tags, optionally only of one account's items.
+pub async fn get_rule_tags(
+    pool: &DatabasePool,
+    account_id: Option<&str>,
+) -> Result<Vec<models::StatementItemTag>, sqlx::Error> {
+    tracing::debug!("Retrieving statement item tags from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_as!(
+        models::StatementItemTag,
+        "SELECT t.statement_item_id, t.tag FROM statement_item_tags t JOIN statement_items s ON s.id = t.statement_item_id WHERE (? IS NULL OR s.account_id = ?)",
+        account_id,
+        account_id,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_as!(
+        models::StatementItemTag,
+        "SELECT t.statement_item_id, t.tag FROM statement_item_tags t JOIN statement_items s ON s.id = t.statement_item_id WHERE ($1::TEXT IS NULL OR s.account_id = $1)",
+        account_id,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::StatementItemTag,
+        "SELECT t.statement_item_id, t.tag FROM statement_item_tags t JOIN statement_items s ON s.id = t.statement_item_id WHERE (? IS NULL OR s.account_id = ?)",
+        account_id,
+        account_id,
+    )
+    .fetch_all(pool)
+    .await;
+
+    result
+}
+
+/// Sets the note of a statement item, replacing an earlier one.
+pub async fn upsert_annotation(
+    pool: &DatabasePool,
+    id: &str,
+    note: &str,
+    updated_at: NaiveDateTime,
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Writing transaction annotation into DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "INSERT INTO transaction_annotations (statement_item_id, note, updated_at) VALUES (?, ?, ?) ON CONFLICT (statement_item_id) DO UPDATE SET note = excluded.note, updated_at = excluded.updated_at",
+        id,
+        note,
+        updated_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "INSERT INTO transaction_annotations (statement_item_id, note, updated_at) VALUES ($1, $2, $3) ON CONFLICT (statement_item_id) DO UPDATE SET note = excluded.note, updated_at = excluded.updated_at",
+        id,
+        note,
+        updated_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "INSERT INTO transaction_annotations (statement_item_id, note, updated_at) VALUES (?, ?, ?) ON DUPLICATE KEY UPDATE note = VALUES(note), updated_at = VALUES(updated_at)",
+        id,
+        note,
+        updated_at,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|_| ())
+}
+
+/// Returns whether there was a note to delete.
+pub async fn delete_annotation(pool: &DatabasePool, id: &str) -> Result<bool, sqlx::Error> {
+    tracing::debug!("Deleting transaction annotation from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "DELETE FROM transaction_annotations WHERE statement_item_id = ?",
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "DELETE FROM transaction_annotations WHERE statement_item_id = $1",
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "DELETE FROM transaction_annotations WHERE statement_item_id = ?",
+        id,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|r| r.rows_affected() > 0)
+}
+
+pub async fn get_annotation(
+    pool: &DatabasePool,
+    id: &str,
+) -> Result<Option<models::TransactionAnnotation>, sqlx::Error> {
+    tracing::debug!("Retrieving transaction annotation from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_as!(
+        models::TransactionAnnotation,
+        r#"SELECT note, updated_at as "updated_at: NaiveDateTime" FROM transaction_annotations WHERE statement_item_id = ?"#,
+        id,
+    )
+    .fetch_optional(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_as!(
+        models::TransactionAnnotation,
+        "SELECT note, updated_at FROM transaction_annotations WHERE statement_item_id = $1",
+        id,
+    )
+    .fetch_optional(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::TransactionAnnotation,
+        "SELECT note, updated_at FROM transaction_annotations WHERE statement_item_id = ?",
+        id,
+    )
+    .fetch_optional(pool)
+    .await;
+
+    result
+}
+
+/// Returns whether the tag is new on the statement item.
+pub async fn insert_transaction_tag(
+    pool: &DatabasePool,
+    id: &str,
+    tag: &str,
+    created_at: NaiveDateTime,
+) -> Result<bool, sqlx::Error> {
+    tracing::debug!("Writing transaction tag into DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "INSERT OR IGNORE INTO transaction_tags (statement_item_id, tag, created_at) VALUES (?, ?, ?)",
+        id,
+        tag,
+        created_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "INSERT INTO transaction_tags (statement_item_id, tag, created_at) VALUES ($1, $2, $3) ON CONFLICT (statement_item_id, tag) DO NOTHING",
+        id,
+        tag,
+        created_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = skip_duplicate(
+        sqlx::query!(
+            "INSERT INTO transaction_tags (statement_item_id, tag, created_at) VALUES (?, ?, ?)",
+            id,
+            tag,
+            created_at,
+        )
+        .execute(pool)
+        .await,
+    );
+
+    result.map(|r| r.rows_affected() > 0)
+}
+
+/// Returns whether the statement item had the tag.
+pub async fn delete_transaction_tag(
+    pool: &DatabasePool,
+    id: &str,
+    tag: &str,
+) -> Result<bool, sqlx::Error> {
+    tracing::debug!("Deleting transaction tag from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "DELETE FROM transaction_tags WHERE statement_item_id = ? AND tag = ?",
+        id,
+        tag,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "DELETE FROM transaction_tags WHERE statement_item_id = $1 AND tag = $2",
+        id,
+        tag,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query!(
+        "DELETE FROM transaction_tags WHERE statement_item_id = ? AND tag = ?",
+        id,
+        tag,
+    )
+    .execute(pool)
+    .await;
+
+    result.map(|r| r.rows_affected() > 0)
+}
+
+pub async fn get_transaction_tags(
+    pool: &DatabasePool,
+    id: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    tracing::debug!("Retrieving transaction tags from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_scalar!(
+        "SELECT tag FROM transaction_tags WHERE statement_item_id = ? ORDER BY tag",
+        id,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_scalar!(
+        "SELECT tag FROM transaction_tags WHERE statement_item_id = $1 ORDER BY tag",
+        id,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_scalar!(
+        "SELECT tag FROM transaction_tags WHERE statement_item_id = ? ORDER BY tag",
+        id,
+    )
+    .fetch_all(pool)
+    .await;
+
+    result
+}
+
+/// Items of accounts with a known client that are on neither side of an internal transfer.
+pub async fn get_transfer_candidates(
+    pool: &DatabasePool,
+    since: Option<NaiveDateTime>,
+) -> Result<Vec<models::TransferCandidate>, sqlx::Error> {
+    tracing::debug!("Retrieving transfer candidates from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_as!(
+        models::TransferCandidate,
+        r#"SELECT s.id as "id!", s.account_id, a.client_id, s.time as "time: NaiveDateTime", s.description, s.amount, s.operation_amount, s.currency_code, a.currency_code as account_currency, s.counter_iban FROM statement_items s JOIN accounts a ON a.id = s.account_id WHERE (? IS NULL OR s.time >= ?) AND s.id NOT IN (SELECT outflow_item_id FROM internal_transfers) AND s.id NOT IN (SELECT inflow_item_id FROM internal_transfers) ORDER BY s.time, s.id"#,
+        since,
+        since,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_as!(
+        models::TransferCandidate,
+        r#"SELECT s.id, s.account_id, a.client_id, s.time, s.description, s.amount, s.operation_amount, s.currency_code::BIGINT as "currency_code!", a.currency_code::BIGINT as "account_currency!", s.counter_iban FROM statement_items s JOIN accounts a ON a.id = s.account_id WHERE ($1::TIMESTAMP IS NULL OR s.time >= $1) AND s.id NOT IN (SELECT outflow_item_id FROM internal_transfers) AND s.id NOT IN (SELECT inflow_item_id FROM internal_transfers) ORDER BY s.time, s.id"#,
+        since,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::TransferCandidate,
+        r#"SELECT s.id, s.account_id, a.client_id, s.time as "time: NaiveDateTime", s.description, s.amount, s.operation_amount, CAST(s.currency_code AS SIGNED) as "currency_code!: i64", CAST(a.currency_code AS SIGNED) as "account_currency!: i64", s.counter_iban FROM statement_items s JOIN accounts a ON a.id = s.account_id WHERE (? IS NULL OR s.time >= ?) AND s.id NOT IN (SELECT outflow_item_id FROM internal_transfers) AND s.id NOT IN (SELECT inflow_item_id FROM internal_transfers) ORDER BY s.time, s.id"#,
+        since,
+        since,
+    )
+    .fetch_all(pool)
+    .await;
+
+    result
+}
+
+pub async fn get_account_ibans(
+    pool: &DatabasePool,
+) -> Result<Vec<models::AccountIban>, sqlx::Error> {
+    tracing::debug!("Retrieving account IBANs from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_as!(
+        models::AccountIban,
+        r#"SELECT client_id, iban as "iban!" FROM accounts WHERE iban IS NOT NULL"#,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_as!(
+        models::AccountIban,
+        r#"SELECT client_id, iban as "iban!" FROM accounts WHERE iban IS NOT NULL"#,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::AccountIban,
+        r#"SELECT client_id, iban as "iban!" FROM accounts WHERE iban IS NOT NULL"#,
+    )
+    .fetch_all(pool)
+    .await;
+
+    result
+}
+
+/// Returns whether the pair is new.
+pub async fn insert_internal_transfer(
+    pool: &DatabasePool,
+    outflow_item_id: &str,
+    inflow_item_id: &str,
+    matched_by: &str,
+    matched_at: NaiveDateTime,
+) -> Result<bool, sqlx::Error> {
+    tracing::debug!("Writing internal transfer into DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "INSERT OR IGNORE INTO internal_transfers (outflow_item_id, inflow_item_id, matched_by, matched_at) VALUES (?, ?, ?, ?)",
+        outflow_item_id,
+        inflow_item_id,
+        matched_by,
+        matched_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "INSERT INTO internal_transfers (outflow_item_id, inflow_item_id, matched_by, matched_at) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        outflow_item_id,
+        inflow_item_id,
+        matched_by,
+        matched_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = skip_duplicate(sqlx::query!(
+        "INSERT INTO internal_transfers (outflow_item_id, inflow_item_id, matched_by, matched_at) VALUES (?, ?, ?, ?)",
+        outflow_item_id,
+        inflow_item_id,
+        matched_by,
+        matched_at,
+    )
+    .execute(pool)
+    .await);
+
+    result.map(|r| r.rows_affected() > 0)
+}
+
+pub async fn get_account_balances(
+    pool: &DatabasePool,
+) -> Result<Vec<models::AccountBalance>, sqlx::Error> {
+    tracing::debug!("Retrieving account balances from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_as!(
+        models::AccountBalance,
+        r#"SELECT id as "id!", account_type, balance, credit_limit, currency_code, iban, last_sync_at as "last_sync_at: _" FROM accounts ORDER BY id"#,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_as!(
+        models::AccountBalance,
+        r#"SELECT id, account_type, balance, credit_limit, currency_code::BIGINT as "currency_code!", iban, last_sync_at as "last_sync_at: _" FROM accounts ORDER BY id"#,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_as!(
+        models::AccountBalance,
+        r#"SELECT id, account_type, balance, credit_limit, CAST(currency_code AS SIGNED) as "currency_code!: i64", iban, last_sync_at as "last_sync_at: _" FROM accounts ORDER BY id"#,
+    )
+    .fetch_all(pool)
+    .await;
+
+    result
+}
+
+/// Ids of the items already written to the journal `target` in `format`.
+pub async fn get_exported_item_ids(
+    pool: &DatabasePool,
+    format: &str,
+    target: &str,
+) -> Result<Vec<String>, sqlx::Error> {
+    tracing::debug!("Retrieving exported item ids from DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query_scalar!(
+        "SELECT statement_item_id FROM journal_exports WHERE format = ? AND target = ?",
+        format,
+        target,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query_scalar!(
+        "SELECT statement_item_id FROM journal_exports WHERE format = $1 AND target = $2",
+        format,
+        target,
+    )
+    .fetch_all(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = sqlx::query_scalar!(
+        "SELECT statement_item_id FROM journal_exports WHERE format = ? AND target = ?",
+        format,
+        target,
+    )
+    .fetch_all(pool)
+    .await;
+
+    result
+}
+
+pub async fn insert_exported_item(
+    pool: &DatabasePool,
+    format: &str,
+    target: &str,
+    id: &str,
+    exported_at: NaiveDateTime,
+) -> Result<(), sqlx::Error> {
+    tracing::debug!("Recording exported item in DB...");
+
+    #[cfg(feature = "sqlite")]
+    let result = sqlx::query!(
+        "INSERT OR IGNORE INTO journal_exports (format, target, statement_item_id, exported_at) VALUES (?, ?, ?, ?)",
+        format,
+        target,
+        id,
+        exported_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "postgres")]
+    let result = sqlx::query!(
+        "INSERT INTO journal_exports (format, target, statement_item_id, exported_at) VALUES ($1, $2, $3, $4) ON CONFLICT DO NOTHING",
+        format,
+        target,
+        id,
+        exported_at,
+    )
+    .execute(pool)
+    .await;
+
+    #[cfg(feature = "mysql")]
+    let result = skip_duplicate(sqlx::query!(
+        "INSERT INTO journal_exports (format, target, statement_item_id, exported_at) VALUES (?, ?, ?, ?)",
+        format,
+        target,
+        id,
+        exported_at,
+    )
+    .execute(pool)
+    .await);
+
     result.map(|_| ())
 }
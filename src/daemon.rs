@@ -0,0 +1,53 @@
+use crate::schedule::Schedule;
+use crate::sync::Syncer;
+use crate::{config, utils};
+use serde_with::chrono::Utc;
+
+/// Syncs on a schedule until a shutdown is requested.
+///
+/// SIGHUP re-reads `.env` and applies it from the next run on; `fixed_schedule`,
+/// when given on the command line, wins over `SYNC_SCHEDULE` across reloads.
+pub async fn run(syncer: &Syncer, fixed_schedule: Option<Schedule>) {
+    let mut schedule = match fixed_schedule.clone() {
+        Some(schedule) => schedule,
+        None => config::get_sync_schedule().expect("SYNC_SCHEDULE must be an interval or cron"),
+    };
+    #[cfg(unix)]
+    let mut hangup = tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup())
+        .expect("Failed to listen for SIGHUP");
+    tracing::info!(%schedule, "Starting sync daemon");
+    loop {
+        let started_at = Utc::now();
+        let tokens = config::get_multiple_monobank_tokens();
+        let time_floor = utils::datetime_from(config::get_sync_start_timestamp());
+        syncer.sync_all(&tokens, time_floor).await;
+        loop {
+            if syncer.shutdown.requested() {
+                tracing::info!("Sync daemon stopped");
+                return;
+            }
+            let next_run = schedule.next_run(started_at);
+            tracing::info!(next_run = %next_run.with_timezone(&config::get_timezone()), "Waiting for the next sync");
+            let delay = (next_run - Utc::now()).to_std().unwrap_or_default();
+            #[cfg(unix)]
+            let reload = hangup.recv();
+            #[cfg(not(unix))]
+            let reload = std::future::pending::<Option<()>>();
+            tokio::select! {
+                _ = tokio::time::sleep(delay) => break,
+                _ = syncer.shutdown.wait() => {}
+                _ = reload => {
+                    config::reload_env();
+                    syncer.reload_categorizer();
+                    if fixed_schedule.is_none() {
+                        match config::get_sync_schedule() {
+                            Ok(reloaded) => schedule = reloaded,
+                            Err(e) => tracing::warn!("Keeping the previous schedule: {}", e),
+                        }
+                    }
+                    tracing::info!(%schedule, "Configuration reloaded");
+                }
+            }
+        }
+    }
+}
@@ -1,4 +1,6 @@
 use crate::db_types::DatabasePool;
+#[cfg(feature = "mysql")]
+use sqlx::MySqlPool;
 #[cfg(feature = "postgres")]
 use sqlx::PgPool;
 #[cfg(feature = "sqlite")]
@@ -15,6 +17,11 @@ pub async fn initialize(database_url: &str) -> DatabasePool {
         .await
         .expect("Failed to connect to SQLite database");
 
+    #[cfg(feature = "mysql")]
+    let pool = MySqlPool::connect(database_url)
+        .await
+        .expect("Failed to connect to MySQL database");
+
     #[cfg(feature = "postgres")]
     sqlx::migrate!("./migrations/postgres")
         .run(&pool)
@@ -27,5 +34,11 @@ pub async fn initialize(database_url: &str) -> DatabasePool {
         .await
         .expect("Failed to migrate database");
 
+    #[cfg(feature = "mysql")]
+    sqlx::migrate!("./migrations/mysql")
+        .run(&pool)
+        .await
+        .expect("Failed to migrate database");
+
     pool
 }
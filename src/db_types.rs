@@ -1,3 +1,5 @@
+#[cfg(feature = "mysql")]
+use sqlx::MySqlPool;
 #[cfg(feature = "postgres")]
 use sqlx::PgPool;
 #[cfg(feature = "sqlite")]
@@ -7,3 +9,12 @@ use sqlx::SqlitePool;
 pub type DatabasePool = PgPool;
 #[cfg(feature = "sqlite")]
 pub type DatabasePool = SqlitePool;
+#[cfg(feature = "mysql")]
+pub type DatabasePool = MySqlPool;
+
+#[cfg(feature = "postgres")]
+pub type Database = sqlx::Postgres;
+#[cfg(feature = "sqlite")]
+pub type Database = sqlx::Sqlite;
+#[cfg(feature = "mysql")]
+pub type Database = sqlx::MySql;
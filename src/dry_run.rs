@@ -0,0 +1,115 @@
+use crate::sync::{self, Syncer};
+use crate::{api, crud, utils};
+use serde_with::chrono::NaiveDateTime;
+
+/// Walks through a sync like `Syncer::sync_all` would, but only reads from the database.
+///
+/// Client info is always requested; statement windows are fetched only with `fetch`,
+/// which is as rate-limited as a real sync.
+pub async fn run(syncer: &Syncer, tokens: &[String], time_floor: NaiveDateTime, fetch: bool) {
+    let now = utils::current_timestamp();
+    let known_accounts = match crud::get_account_ids(&syncer.pool).await {
+        Ok(ids) => ids,
+        Err(e) => {
+            tracing::error!("Failed to list accounts: {:?}", e);
+            return;
+        }
+    };
+    let mut total_windows = 0;
+    let mut total_new_items = 0;
+    for token in tokens {
+        let raw_client_info = match api::fetch_client_info(&syncer.client, token).await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::error!("Failed to fetch client info: {:?}", e);
+                continue;
+            }
+        };
+        for raw_account in sync::relevant_accounts(&raw_client_info) {
+            let account_id = raw_account.id;
+            let last_sync_at = crud::get_last_sync_time(&syncer.pool, account_id.clone())
+                .await
+                .ok()
+                .flatten();
+            let action = if known_accounts.contains(&account_id) {
+                "update"
+            } else {
+                "create"
+            };
+            let cursor = utils::timestamp_from(last_sync_at.unwrap_or(time_floor)) - 1;
+            let windows = api::plan_windows(cursor, now);
+            tracing::info!(
+                account_id,
+                action,
+                account_type = raw_account.account_type,
+                cursor = %utils::datetime_from(cursor + 1),
+                windows = windows.len(),
+                "Would sync account",
+            );
+            total_windows += windows.len();
+            if !fetch {
+                for (start, end) in windows {
+                    tracing::info!(
+                        account_id,
+                        from = %utils::datetime_from(start),
+                        to = %utils::datetime_from(end),
+                        "Would request window",
+                    );
+                }
+                continue;
+            }
+            let mut card_statements = api::FetchingStatements {
+                client: &syncer.client,
+                rate_limiter: &syncer.rate_limiter,
+                token: token.to_string(),
+                account_id: account_id.clone(),
+                last_success_time: cursor,
+                end_time: now,
+            };
+            let mut window_start = cursor + 1;
+            while let Some(statement_response) = card_statements.fetch_next().await {
+                let (window_end, raw_statements) = match statement_response {
+                    Ok(window) => window,
+                    Err((_, e)) => {
+                        tracing::error!("Error fetching statements: {:?}", e);
+                        break;
+                    }
+                };
+                let mut new_items = 0;
+                for item in &raw_statements {
+                    if !crud::statement_item_exists(&syncer.pool, &item.id)
+                        .await
+                        .unwrap_or(false)
+                    {
+                        new_items += 1;
+                    }
+                }
+                tracing::info!(
+                    account_id,
+                    from = %utils::datetime_from(window_start),
+                    to = %utils::datetime_from(window_end),
+                    fetched = raw_statements.len(),
+                    new_items,
+                    "Would insert statement items",
+                );
+                total_new_items += new_items;
+                window_start = window_end + 1;
+            }
+        }
+    }
+    let eta_minutes = sync::eta_minutes(total_windows);
+    if fetch {
+        tracing::info!(
+            windows = total_windows,
+            eta_minutes,
+            new_items = total_new_items,
+            "Dry run finished, nothing written"
+        );
+    } else {
+        tracing::info!(
+            windows = total_windows,
+            eta_minutes,
+            "Dry run finished, nothing written"
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Getting statement items out of the database in formats other tools read.
+
+pub mod budget;
+pub mod csv;
+pub mod journal;
+pub mod ofx;
+pub mod qif;
+
+use crate::db_types::DatabasePool;
+use crate::{crud, models, utils};
+use serde_with::chrono::NaiveDateTime;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+pub type Accounts = HashMap<String, models::AccountBalance>;
+
+/// Currency of the item's account, hryvnias for items of accounts that are gone.
+pub fn account_currency(accounts: &Accounts, item: &models::StatementItem) -> u32 {
+    accounts
+        .get(&item.account_id)
+        .map_or(980, |account| account.currency_code as u32)
+}
+
+/// Items grouped by account, accounts in the order of their first item.
+pub fn by_account(items: &[models::StatementItem]) -> Vec<(&str, Vec<&models::StatementItem>)> {
+    let mut groups: Vec<(&str, Vec<&models::StatementItem>)> = Vec::new();
+    for item in items {
+        match groups.iter_mut().find(|(id, _)| *id == item.account_id) {
+            Some((_, group)) => group.push(item),
+            None => groups.push((&item.account_id, vec![item])),
+        }
+    }
+    groups
+}
+
+/// Which items to export: some accounts or all of them, within an optional time range.
+#[derive(Debug, Clone, Default)]
+pub struct Selection {
+    pub accounts: Vec<String>,
+    pub from: Option<NaiveDateTime>,
+    pub to: Option<NaiveDateTime>,
+}
+
+/// Selected items ordered by time, with every stored account by id.
+pub async fn load(
+    pool: &DatabasePool,
+    selection: &Selection,
+) -> Result<(Vec<models::StatementItem>, Accounts), sqlx::Error> {
+    let accounts = crud::get_account_balances(pool)
+        .await?
+        .into_iter()
+        .map(|account| (account.id.clone(), account))
+        .collect::<Accounts>();
+    let range = match (selection.from, selection.to) {
+        (None, None) => None,
+        (from, to) => Some((
+            from.unwrap_or_else(|| utils::datetime_from(0)),
+            to.unwrap_or_else(|| utils::datetime_from(u32::MAX)),
+        )),
+    };
+    let mut items = Vec::new();
+    if selection.accounts.is_empty() {
+        items = crud::get_statement_items(pool, None, range).await?;
+    } else {
+        for account_id in &selection.accounts {
+            if !accounts.contains_key(account_id) {
+                tracing::warn!(account_id, "Unknown account, nothing to export for it");
+            }
+            items.extend(crud::get_statement_items(pool, Some(account_id), range).await?);
+        }
+        items.sort_by(|a, b| a.time.cmp(&b.time).then_with(|| a.id.cmp(&b.id)));
+    }
+    Ok((items, accounts))
+}
+
+/// A file at `path`, appended to with `append`, or stdout without one.
+pub fn open_output(path: Option<&Path>, append: bool) -> io::Result<Box<dyn Write>> {
+    Ok(match path {
+        Some(path) => {
+            let file = OpenOptions::new()
+                .write(true)
+                .create(true)
+                .append(append)
+                .truncate(!append)
+                .open(path)?;
+            Box::new(BufWriter::new(file))
+        }
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    })
+}
+
+/// Writes the selected items with `write`, which returns how many rows it wrote.
+pub async fn run<E: fmt::Display>(
+    pool: &DatabasePool,
+    selection: &Selection,
+    output: Option<&Path>,
+    write: impl FnOnce(&[models::StatementItem], &Accounts, Box<dyn Write>) -> Result<usize, E>,
+) {
+    let (items, accounts) = match load(pool, selection).await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            tracing::error!("Failed to get statement items: {:?}", e);
+            return;
+        }
+    };
+    let out = match open_output(output, false) {
+        Ok(out) => out,
+        Err(e) => {
+            tracing::error!("Failed to open output: {}", e);
+            return;
+        }
+    };
+    match write(&items, &accounts, out) {
+        Ok(rows) => tracing::info!(rows, "Exported statement items"),
+        Err(e) => tracing::error!("Failed to export statement items: {}", e),
+    }
+}
+
+/// Writes a journal, with `incremental` only the selected items not written to the same
+/// output before, appending to it.
+pub async fn run_journal(
+    pool: &DatabasePool,
+    selection: &Selection,
+    flavor: journal::Flavor,
+    map: &journal::AccountMap,
+    output: Option<&Path>,
+    incremental: bool,
+) {
+    let (mut items, accounts) = match load(pool, selection).await {
+        Ok(loaded) => loaded,
+        Err(e) => {
+            tracing::error!("Failed to get statement items: {:?}", e);
+            return;
+        }
+    };
+    let target = output.map_or("-".to_string(), |path| path.display().to_string());
+    let mut previous = Vec::new();
+    if incremental {
+        let exported = match crud::get_exported_item_ids(pool, flavor.name(), &target).await {
+            Ok(ids) => ids.into_iter().collect::<HashSet<String>>(),
+            Err(e) => {
+                tracing::error!("Failed to get exported items: {:?}", e);
+                return;
+            }
+        };
+        items.retain(|item| !exported.contains(&item.id));
+        // Everything already in the output, whatever this run selects, so accounts are
+        // opened once and the newest exported day is known
+        previous = match load(pool, &Selection::default()).await {
+            Ok((all, _)) => all
+                .into_iter()
+                .filter(|item| exported.contains(&item.id))
+                .collect(),
+            Err(e) => {
+                tracing::error!("Failed to get exported statement items: {:?}", e);
+                return;
+            }
+        };
+    }
+    if items.is_empty() {
+        tracing::info!("No new statement items to export");
+        return;
+    }
+    let result = open_output(output, incremental)
+        .and_then(|out| journal::write(&items, &previous, &accounts, map, flavor, out));
+    let transactions = match result {
+        Ok(transactions) => transactions,
+        Err(e) => {
+            tracing::error!("Failed to write journal: {}", e);
+            return;
+        }
+    };
+    if incremental {
+        let now = utils::datetime_from(utils::current_timestamp());
+        for item in &items {
+            if let Err(e) =
+                crud::insert_exported_item(pool, flavor.name(), &target, &item.id, now).await
+            {
+                tracing::warn!("Failed to record exported item {}: {:?}", item.id, e);
+            }
+        }
+    }
+    tracing::info!(transactions, "Exported journal");
+}
+
+/// Items and accounts the exporters' tests write.
+#[cfg(test)]
+pub(crate) mod fixtures {
+    use super::Accounts;
+    use crate::models;
+    pub use crate::testing::at;
+    use serde_with::chrono::NaiveDateTime;
+
+    /// A hryvnia black card and a dollar one.
+    pub fn accounts() -> Accounts {
+        [("black", "black", 980), ("usd", "white", 840)]
+            .into_iter()
+            .map(|(id, account_type, currency_code)| {
+                let account = models::AccountBalance {
+                    id: id.to_string(),
+                    account_type: account_type.to_string(),
+                    balance: 0,
+                    credit_limit: 0,
+                    currency_code,
+                    iban: None,
+                    last_sync_at: None,
+                };
+                (id.to_string(), account)
+            })
+            .collect()
+    }
+
+    /// A coffee in the currency of the account, dollars on `usd`.
+    pub fn item(
+        id: &str,
+        account_id: &str,
+        time: NaiveDateTime,
+        amount: i64,
+        balance: i64,
+    ) -> models::StatementItem {
+        models::StatementItem {
+            currency_code: if account_id == "usd" { 840 } else { 980 },
+            balance,
+            ..crate::testing::statement_item(id, account_id, time, amount)
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::export::{self, Accounts};
+use crate::models;
+use crate::money::{self, Money};
+use std::io::Write;
+
+/// Budgeting app whose CSV import an export is shaped for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum App {
+    /// Firefly III's data importer, with the item id as external id so it skips duplicates.
+    Firefly,
+    /// Actual Budget, whose importer matches these column names by itself.
+    Actual,
+    /// YNAB file import, which has no category column and splits amounts by sign.
+    Ynab,
+}
+
+impl App {
+    fn header(self) -> &'static [&'static str] {
+        match self {
+            App::Firefly => &[
+                "date",
+                "amount",
+                "currency_code",
+                "description",
+                "opposing_name",
+                "opposing_iban",
+                "notes",
+                "category",
+                "asset_account_iban",
+                "external_id",
+            ],
+            App::Actual => &["Date", "Payee", "Notes", "Category", "Amount"],
+            App::Ynab => &["Date", "Payee", "Memo", "Outflow", "Inflow"],
+        }
+    }
+
+    /// Actual and YNAB import into one account at a time.
+    fn single_account(self) -> bool {
+        matches!(self, App::Actual | App::Ynab)
+    }
+}
+
+/// Writes a header and a row per item in `app`'s shape, returning the number of rows.
+///
+/// The payee is the counterparty's name, or the description for items without one,
+/// and the memo is the item's comment. Amounts are decimals in the account's currency.
+pub fn write(
+    items: &[models::StatementItem],
+    accounts: &Accounts,
+    app: App,
+    output: impl Write,
+) -> Result<usize, csv::Error> {
+    if app.single_account() && export::by_account(items).len() > 1 {
+        tracing::warn!("Exporting several accounts into one file, pick one with --account");
+    }
+    let mut writer = csv::Writer::from_writer(output);
+    writer.write_record(app.header())?;
+    for item in items {
+        let currency = export::account_currency(accounts, item);
+        let amount = item.amount_money(currency);
+        let payee = item.counter_name.as_deref().unwrap_or(&item.description);
+        let memo = item.comment.as_deref().unwrap_or_default();
+        let category = item.category.as_deref().unwrap_or_default();
+        let date = item.time.format("%Y-%m-%d").to_string();
+        let record = match app {
+            App::Firefly => vec![
+                item.time.format("%Y-%m-%dT%H:%M:%S").to_string(),
+                amount.decimal(),
+                money::alpha_code(currency),
+                item.description.clone(),
+                payee.to_string(),
+                item.counter_iban.clone().unwrap_or_default(),
+                memo.to_string(),
+                category.to_string(),
+                accounts
+                    .get(&item.account_id)
+                    .and_then(|account| account.iban.clone())
+                    .unwrap_or_default(),
+                item.id.clone(),
+            ],
+            App::Actual => vec![
+                date,
+                payee.to_string(),
+                memo.to_string(),
+                category.to_string(),
+                amount.decimal(),
+            ],
+            App::Ynab => {
+                let (outflow, inflow) = if amount.minor < 0 {
+                    (Money::new(-amount.minor, currency).decimal(), String::new())
+                } else {
+                    (String::new(), amount.decimal())
+                };
+                vec![date, payee.to_string(), memo.to_string(), outflow, inflow]
+            }
+        };
+        writer.write_record(record)?;
+    }
+    writer.flush()?;
+    Ok(items.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::fixtures::{accounts, at, item};
+
+    fn items() -> Vec<models::StatementItem> {
+        let mut paid = item("a", "black", at(1, 8), -4_150, 1_000);
+        paid.counter_name = Some("Cafe, Lviv".to_string());
+        paid.counter_iban = Some("UA-cafe".to_string());
+        paid.comment = Some("latte".to_string());
+        let mut refund = item("b", "black", at(2, 9), 2_000, 3_000);
+        refund.category = None;
+        vec![paid, refund]
+    }
+
+    fn written(app: App) -> String {
+        let mut accounts = accounts();
+        accounts.get_mut("black").unwrap().iban = Some("UA-black".to_string());
+        let mut out = Vec::new();
+        assert_eq!(write(&items(), &accounts, app, &mut out).unwrap(), 2);
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn firefly_rows_carry_ids_and_ibans() {
+        assert_eq!(
+            written(App::Firefly),
+            "date,amount,currency_code,description,opposing_name,opposing_iban,notes,category,asset_account_iban,external_id\n\
+             2026-10-01T08:00:00,-41.50,UAH,Coffee Point,\"Cafe, Lviv\",UA-cafe,latte,cafes,UA-black,a\n\
+             2026-10-02T09:00:00,20.00,UAH,Coffee Point,Coffee Point,,,,UA-black,b\n"
+        );
+    }
+
+    #[test]
+    fn actual_rows_have_signed_amounts() {
+        assert_eq!(
+            written(App::Actual),
+            "Date,Payee,Notes,Category,Amount\n\
+             2026-10-01,\"Cafe, Lviv\",latte,cafes,-41.50\n\
+             2026-10-02,Coffee Point,,,20.00\n"
+        );
+    }
+
+    #[test]
+    fn ynab_rows_split_amounts_by_sign() {
+        assert_eq!(
+            written(App::Ynab),
+            "Date,Payee,Memo,Outflow,Inflow\n\
+             2026-10-01,\"Cafe, Lviv\",latte,41.50,\n\
+             2026-10-02,Coffee Point,,,20.00\n"
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use crate::export::{self, Accounts};
+use crate::models;
+use crate::money::{self, Money};
+use std::fmt;
+use std::io::Write;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Column {
+    Id,
+    AccountId,
+    Time,
+    Description,
+    Mcc,
+    OriginalMcc,
+    Category,
+    Hold,
+    /// In the account's currency, like `commission_rate`, `cashback_amount` and `balance`.
+    Amount,
+    OperationAmount,
+    /// Alpha code of the operation's currency.
+    Currency,
+    /// Alpha code of the account's currency.
+    AccountCurrency,
+    CommissionRate,
+    CashbackAmount,
+    Balance,
+    Comment,
+    ReceiptId,
+    InvoiceId,
+    CounterEdrpou,
+    CounterIban,
+    CounterName,
+}
+
+impl Column {
+    const ALL: &'static [(&'static str, Column)] = &[
+        ("id", Column::Id),
+        ("account_id", Column::AccountId),
+        ("time", Column::Time),
+        ("description", Column::Description),
+        ("mcc", Column::Mcc),
+        ("original_mcc", Column::OriginalMcc),
+        ("category", Column::Category),
+        ("hold", Column::Hold),
+        ("amount", Column::Amount),
+        ("operation_amount", Column::OperationAmount),
+        ("currency", Column::Currency),
+        ("account_currency", Column::AccountCurrency),
+        ("commission_rate", Column::CommissionRate),
+        ("cashback_amount", Column::CashbackAmount),
+        ("balance", Column::Balance),
+        ("comment", Column::Comment),
+        ("receipt_id", Column::ReceiptId),
+        ("invoice_id", Column::InvoiceId),
+        ("counter_edrpou", Column::CounterEdrpou),
+        ("counter_iban", Column::CounterIban),
+        ("counter_name", Column::CounterName),
+    ];
+
+    /// What `--columns` defaults to.
+    pub const DEFAULT: &'static str =
+        "time,account_id,description,category,amount,account_currency,operation_amount,currency,balance,counter_name,comment";
+
+    fn name(self) -> &'static str {
+        Self::ALL
+            .iter()
+            .find(|(_, column)| *column == self)
+            .map(|(name, _)| *name)
+            .expect("Every column has a name")
+    }
+}
+
+impl FromStr for Column {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        Self::ALL
+            .iter()
+            .find(|(name, _)| *name == value)
+            .map(|(_, column)| *column)
+            .ok_or_else(|| {
+                let names = Self::ALL.iter().map(|(name, _)| *name).collect::<Vec<_>>();
+                format!(
+                    "unknown column '{value}', expected one of {}",
+                    names.join(", ")
+                )
+            })
+    }
+}
+
+impl fmt::Display for Column {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// How amounts are written: raw minor units like `-12345` or decimals like `-123.45`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AmountFormat {
+    Minor,
+    Decimal,
+}
+
+impl FromStr for AmountFormat {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim() {
+            "minor" => Ok(Self::Minor),
+            "decimal" => Ok(Self::Decimal),
+            other => Err(format!("'{other}' is neither 'minor' nor 'decimal'")),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    pub columns: Vec<Column>,
+    pub amounts: AmountFormat,
+    pub decimal_separator: char,
+    pub thousands_separator: Option<char>,
+    pub delimiter: u8,
+}
+
+/// Writes a header and a row per item, returning the number of rows.
+pub fn write(
+    items: &[models::StatementItem],
+    accounts: &Accounts,
+    options: &CsvOptions,
+    output: impl Write,
+) -> Result<usize, csv::Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .delimiter(options.delimiter)
+        .from_writer(output);
+    writer.write_record(options.columns.iter().map(|c| c.name()))?;
+    for item in items {
+        let account_currency = export::account_currency(accounts, item);
+        let amount = |money: Money| match options.amounts {
+            AmountFormat::Minor => money.minor.to_string(),
+            AmountFormat::Decimal => {
+                money.localized(options.decimal_separator, options.thousands_separator)
+            }
+        };
+        let record = options.columns.iter().map(|column| match column {
+            Column::Id => item.id.clone(),
+            Column::AccountId => item.account_id.clone(),
+            Column::Time => item.time.to_string(),
+            Column::Description => item.description.clone(),
+            Column::Mcc => item.mcc.to_string(),
+            Column::OriginalMcc => item.original_mcc.to_string(),
+            Column::Category => item.category.clone().unwrap_or_default(),
+            Column::Hold => item.hold.to_string(),
+            Column::Amount => amount(item.amount_money(account_currency)),
+            Column::OperationAmount => amount(item.operation_money()),
+            Column::Currency => money::alpha_code(item.currency_code),
+            Column::AccountCurrency => money::alpha_code(account_currency),
+            Column::CommissionRate => amount(Money::new(item.commission_rate, account_currency)),
+            Column::CashbackAmount => amount(Money::new(item.cashback_amount, account_currency)),
+            Column::Balance => amount(item.balance_money(account_currency)),
+            Column::Comment => item.comment.clone().unwrap_or_default(),
+            Column::ReceiptId => item.receipt_id.clone().unwrap_or_default(),
+            Column::InvoiceId => item.invoice_id.clone().unwrap_or_default(),
+            Column::CounterEdrpou => item.counter_edrpou.clone().unwrap_or_default(),
+            Column::CounterIban => item.counter_iban.clone().unwrap_or_default(),
+            Column::CounterName => item.counter_name.clone().unwrap_or_default(),
+        });
+        writer.write_record(record.collect::<Vec<String>>())?;
+    }
+    writer.flush()?;
+    Ok(items.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::fixtures::{accounts, at, item};
+
+    fn written(items: &[models::StatementItem], options: &CsvOptions) -> String {
+        let mut out = Vec::new();
+        assert_eq!(
+            write(items, &accounts(), options, &mut out).unwrap(),
+            items.len()
+        );
+        String::from_utf8(out).unwrap()
+    }
+
+    fn options(columns: &str, amounts: AmountFormat) -> CsvOptions {
+        CsvOptions {
+            columns: columns.split(',').map(|c| c.parse().unwrap()).collect(),
+            amounts,
+            decimal_separator: '.',
+            thousands_separator: None,
+            delimiter: b',',
+        }
+    }
+
+    #[test]
+    fn amounts_are_written_in_minor_units_or_decimals() {
+        let mut paid_abroad = item("b", "black", at(2, 9), -4_150, 1_234_567);
+        paid_abroad.operation_amount = -100;
+        paid_abroad.currency_code = 840;
+        paid_abroad.comment = Some("latte, large".to_string());
+        let items = [item("a", "usd", at(1, 8), -250, 10_000), paid_abroad];
+        let columns = "id,time,amount,account_currency,operation_amount,currency,balance,comment";
+
+        assert_eq!(
+            written(&items, &options(columns, AmountFormat::Minor)),
+            "id,time,amount,account_currency,operation_amount,currency,balance,comment\n\
+             a,2026-10-01 08:00:00,-250,USD,-250,USD,10000,\n\
+             b,2026-10-02 09:00:00,-4150,UAH,-100,USD,1234567,\"latte, large\"\n"
+        );
+        assert_eq!(
+            written(&items, &options(columns, AmountFormat::Decimal)),
+            "id,time,amount,account_currency,operation_amount,currency,balance,comment\n\
+             a,2026-10-01 08:00:00,-2.50,USD,-2.50,USD,100.00,\n\
+             b,2026-10-02 09:00:00,-41.50,UAH,-1.00,USD,12345.67,\"latte, large\"\n"
+        );
+    }
+
+    #[test]
+    fn separators_and_delimiter_are_configurable() {
+        let mut options = options("id,balance,category", AmountFormat::Decimal);
+        options.decimal_separator = ',';
+        options.thousands_separator = Some(' ');
+        options.delimiter = b';';
+        let items = [item("a", "black", at(1, 8), -100, 1_234_567)];
+        assert_eq!(
+            written(&items, &options),
+            "id;balance;category\na;12 345,67;cafes\n"
+        );
+    }
+
+    #[test]
+    fn unknown_columns_are_rejected() {
+        let error = "amount,payee"
+            .split(',')
+            .map(str::parse::<Column>)
+            .collect::<Result<Vec<_>, _>>();
+        assert!(error.unwrap_err().starts_with("unknown column 'payee'"));
+        assert!(Column::DEFAULT
+            .split(',')
+            .all(|c| c.parse::<Column>().is_ok()));
+    }
+}
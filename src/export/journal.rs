@@ -0,0 +1,527 @@
+use crate::export::{self, Accounts};
+use crate::money::{self, Money};
+use crate::{models, verify};
+use serde::Deserialize;
+use serde_with::chrono::{Days, NaiveDate};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+
+/// Plain-text accounting syntax to write.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Flavor {
+    /// Understood by both ledger and hledger.
+    Ledger,
+    Beancount,
+}
+
+impl Flavor {
+    pub fn name(self) -> &'static str {
+        match self {
+            Flavor::Ledger => "ledger",
+            Flavor::Beancount => "beancount",
+        }
+    }
+}
+
+/// Which journal accounts statement items are booked to, read from `JOURNAL_ACCOUNTS_FILE`.
+///
+/// Monobank accounts default to `Assets:Monobank:<Type>-<Currency>` and categories to
+/// `Expenses:<Category>`, with uncategorized items on `expense_account` or
+/// `income_account` depending on their sign.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AccountMap {
+    /// Monobank account id to journal account.
+    #[serde(default)]
+    accounts: HashMap<String, String>,
+    /// Category to journal account.
+    #[serde(default)]
+    categories: HashMap<String, String>,
+    #[serde(default = "default_expense_account")]
+    expense_account: String,
+    #[serde(default = "default_income_account")]
+    income_account: String,
+}
+
+fn default_expense_account() -> String {
+    "Expenses:Uncategorized".to_string()
+}
+
+fn default_income_account() -> String {
+    "Income:Uncategorized".to_string()
+}
+
+impl Default for AccountMap {
+    fn default() -> Self {
+        Self {
+            accounts: HashMap::new(),
+            categories: HashMap::new(),
+            expense_account: default_expense_account(),
+            income_account: default_income_account(),
+        }
+    }
+}
+
+impl AccountMap {
+    /// Reads `path`, or maps everything by default without one.
+    pub fn load(path: Option<&str>) -> Result<Self, String> {
+        match path {
+            Some(path) => {
+                let content =
+                    fs::read_to_string(path).map_err(|e| format!("Failed to read {path}: {e}"))?;
+                Self::from_toml(&content).map_err(|e| format!("Invalid {path}: {e}"))
+            }
+            None => Ok(Self::default()),
+        }
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        toml::from_str(content).map_err(|e| e.to_string())
+    }
+
+    fn asset(&self, accounts: &Accounts, item: &models::StatementItem) -> String {
+        if let Some(name) = self.accounts.get(&item.account_id) {
+            return name.clone();
+        }
+        let currency = export::account_currency(accounts, item);
+        let account_type = accounts
+            .get(&item.account_id)
+            .map_or("Unknown", |account| account.account_type.as_str());
+        format!(
+            "Assets:Monobank:{}-{}",
+            component(account_type),
+            money::alpha_code(currency)
+        )
+    }
+
+    fn counter(&self, item: &models::StatementItem) -> String {
+        match &item.category {
+            Some(category) => self
+                .categories
+                .get(category)
+                .cloned()
+                .unwrap_or_else(|| format!("Expenses:{}", component(category))),
+            None if item.amount < 0 => self.expense_account.clone(),
+            None => self.income_account.clone(),
+        }
+    }
+}
+
+/// A name part beancount accepts: capitalized, letters, digits and dashes only.
+fn component(name: &str) -> String {
+    let cleaned = name
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<&str>>()
+        .join("-");
+    let mut chars = cleaned.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => "Other".to_string(),
+    }
+}
+
+fn quoted(text: &str) -> String {
+    let escaped = single_line(text).replace('\\', "\\\\").replace('"', "\\\"");
+    format!("\"{escaped}\"")
+}
+
+/// Payees, narrations and comments end at the line break in both flavors.
+fn single_line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+/// Journal account for the balance accounts had before their first exported item.
+const OPENING_BALANCES: &str = "Equity:Opening-Balances";
+
+/// Writes one transaction per item, each asserting the account balance after it.
+/// `previous` are the items already in the output, and accounts none of them used start
+/// with an opening balance transaction, so the assertions hold when history starts after
+/// the account did. Items on hold get no assertion, since their amount may still change.
+///
+/// Beancount asserts balances at the start of a day, so it gets a `balance` directive for
+/// the day after each complete day instead, and `open` directives for new accounts. Days
+/// before the newest item's are complete, and the newest one is asserted by the first
+/// later export with newer items.
+///
+/// Items of accounts in currencies missing from `money` are skipped with a warning, as
+/// their numeric code is no valid commodity. Returns how many transactions were written.
+pub fn write(
+    items: &[models::StatementItem],
+    previous: &[models::StatementItem],
+    accounts: &Accounts,
+    map: &AccountMap,
+    flavor: Flavor,
+    mut out: impl Write,
+) -> io::Result<usize> {
+    let has_commodity = |item: &models::StatementItem| {
+        money::Currency::from_numeric(export::account_currency(accounts, item)).is_some()
+    };
+    let (items, unknown): (Vec<models::StatementItem>, Vec<models::StatementItem>) =
+        items.iter().cloned().partition(|item| has_commodity(item));
+    for item in &unknown {
+        tracing::warn!(
+            item_id = item.id,
+            currency = export::account_currency(accounts, item),
+            "Skipping statement item in a currency without a commodity"
+        );
+    }
+    let previous = previous
+        .iter()
+        .filter(|item| has_commodity(item))
+        .cloned()
+        .collect::<Vec<models::StatementItem>>();
+    let previous = previous.as_slice();
+    let items = booking_order(&items);
+    let opened = previous
+        .iter()
+        .flat_map(|item| [map.asset(accounts, item), map.counter(item)])
+        .collect::<HashSet<String>>();
+    // The first item of every new asset account, whose balance before it is the opening one
+    let mut first_items: BTreeMap<String, &models::StatementItem> = BTreeMap::new();
+    for item in &items {
+        let asset = map.asset(accounts, item);
+        if !opened.contains(&asset) {
+            first_items.entry(asset).or_insert(item);
+        }
+    }
+
+    if flavor == Flavor::Beancount {
+        let mut opens: BTreeMap<String, NaiveDate> = BTreeMap::new();
+        for item in &items {
+            for name in [map.asset(accounts, item), map.counter(item)] {
+                if !opened.contains(&name) {
+                    opens.entry(name).or_insert(item.time.date());
+                }
+            }
+        }
+        // The first export opens the equity account for every later one too
+        if let Some(first) = items.first().filter(|_| previous.is_empty()) {
+            opens
+                .entry(OPENING_BALANCES.to_string())
+                .or_insert(first.time.date());
+        }
+        for (name, date) in &opens {
+            writeln!(out, "{date} open {name}")?;
+        }
+        if !opens.is_empty() {
+            writeln!(out)?;
+        }
+    }
+
+    for (asset, item) in &first_items {
+        let opening = item.balance - item.amount;
+        if opening == 0 {
+            continue;
+        }
+        let currency = export::account_currency(accounts, item);
+        let date = item.time.date();
+        match flavor {
+            Flavor::Ledger => {
+                writeln!(out, "{date} * Opening balance")?;
+                writeln!(
+                    out,
+                    "    {asset}  {} = {}",
+                    Money::new(opening, currency),
+                    Money::new(opening, currency)
+                )?;
+                writeln!(out, "    {OPENING_BALANCES}")?;
+            }
+            Flavor::Beancount => {
+                writeln!(out, "{date} * \"Opening balance\"")?;
+                writeln!(out, "  {asset}  {}", Money::new(opening, currency))?;
+                writeln!(out, "  {OPENING_BALANCES}")?;
+            }
+        }
+        writeln!(out)?;
+    }
+
+    for item in &items {
+        let currency = export::account_currency(accounts, item);
+        let date = item.time.date();
+        let asset = map.asset(accounts, item);
+        let counter = map.counter(item);
+        let flag = if item.hold { "!" } else { "*" };
+        match flavor {
+            Flavor::Ledger => {
+                writeln!(out, "{date} {flag} {}", single_line(&item.description))?;
+                writeln!(out, "    ; monobank_id: {}", item.id)?;
+                if let Some(comment) = &item.comment {
+                    writeln!(out, "    ; {}", single_line(comment))?;
+                }
+                if item.hold {
+                    writeln!(out, "    {asset}  {}", item.amount_money(currency))?;
+                } else {
+                    writeln!(
+                        out,
+                        "    {asset}  {} = {}",
+                        item.amount_money(currency),
+                        item.balance_money(currency)
+                    )?;
+                }
+                writeln!(out, "    {counter}")?;
+            }
+            Flavor::Beancount => {
+                match &item.counter_name {
+                    Some(payee) => writeln!(
+                        out,
+                        "{date} {flag} {} {}",
+                        quoted(payee),
+                        quoted(&item.description)
+                    )?,
+                    None => writeln!(out, "{date} {flag} {}", quoted(&item.description))?,
+                }
+                writeln!(out, "  monobank_id: {}", quoted(&item.id))?;
+                if let Some(comment) = &item.comment {
+                    writeln!(out, "  comment: {}", quoted(comment))?;
+                }
+                writeln!(out, "  {asset}  {}", item.amount_money(currency))?;
+                writeln!(out, "  {counter}")?;
+            }
+        }
+        writeln!(out)?;
+    }
+    if flavor == Flavor::Beancount {
+        let assertions = closing_balances(&items, previous, accounts, map);
+        for ((date, asset), balance) in &assertions {
+            let next_day = *date + Days::new(1);
+            writeln!(out, "{next_day} balance {asset}  {balance}")?;
+        }
+        if !assertions.is_empty() {
+            writeln!(out)?;
+        }
+    }
+    out.flush()?;
+    Ok(items.len())
+}
+
+/// The balance of every asset account at the end of each day that became complete with
+/// `items`: days before the newest one that got new items, and those from the newest day
+/// of `previous`, which was still open then. Days ending with an item on hold are left out.
+fn closing_balances(
+    items: &[models::StatementItem],
+    previous: &[models::StatementItem],
+    accounts: &Accounts,
+    map: &AccountMap,
+) -> BTreeMap<(NaiveDate, String), String> {
+    let all = previous
+        .iter()
+        .chain(items)
+        .cloned()
+        .collect::<Vec<models::StatementItem>>();
+    let Some(newest) = all.iter().map(|item| item.time.date()).max() else {
+        return BTreeMap::new();
+    };
+    let last_open = previous.iter().map(|item| item.time.date()).max();
+    let changed = items
+        .iter()
+        .map(|item| (item.time.date(), map.asset(accounts, item)))
+        .collect::<HashSet<(NaiveDate, String)>>();
+    let mut last_items: BTreeMap<(NaiveDate, String), models::StatementItem> = BTreeMap::new();
+    for item in booking_order(&all) {
+        let key = (item.time.date(), map.asset(accounts, &item));
+        let reopened = last_open.is_none_or(|last_open| key.0 >= last_open);
+        if key.0 < newest && (reopened || changed.contains(&key)) {
+            last_items.insert(key, item);
+        }
+    }
+    last_items
+        .into_iter()
+        .filter(|(_, item)| !item.hold)
+        .map(|(key, item)| {
+            let currency = export::account_currency(accounts, &item);
+            (key, item.balance_money(currency).to_string())
+        })
+        .collect()
+}
+
+/// Items by time, those of the same account and second in the order their balances chain.
+fn booking_order(items: &[models::StatementItem]) -> Vec<models::StatementItem> {
+    let mut by_account: BTreeMap<&str, Vec<models::StatementItem>> = BTreeMap::new();
+    for item in items {
+        by_account
+            .entry(&item.account_id)
+            .or_default()
+            .push(item.clone());
+    }
+    let mut ordered = by_account
+        .into_values()
+        .flat_map(verify::continuity_order)
+        .collect::<Vec<models::StatementItem>>();
+    // Stable, so every account keeps its chained order within a second
+    ordered.sort_by_key(|item| item.time);
+    ordered
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::fixtures::{accounts, at, item};
+
+    fn written(
+        items: &[models::StatementItem],
+        previous: &[models::StatementItem],
+        map: &AccountMap,
+        flavor: Flavor,
+    ) -> String {
+        let mut out = Vec::new();
+        write(items, previous, &accounts(), map, flavor, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    /// Two complete days on the black card, the last one still on hold on the next day.
+    fn history() -> Vec<models::StatementItem> {
+        let mut held = item("c", "black", at(2, 9), -50, 650);
+        held.hold = true;
+        held.category = None;
+        vec![
+            item("b", "black", at(1, 10), -200, 700),
+            held,
+            item("a", "black", at(1, 8), -100, 900),
+        ]
+    }
+
+    #[test]
+    fn ledger_opens_with_a_balance_and_asserts_settled_items() {
+        assert_eq!(
+            written(&history(), &[], &AccountMap::default(), Flavor::Ledger),
+            "\
+2026-10-01 * Opening balance
+    Assets:Monobank:Black-UAH  10.00 UAH = 10.00 UAH
+    Equity:Opening-Balances
+
+2026-10-01 * Coffee Point
+    ; monobank_id: a
+    Assets:Monobank:Black-UAH  -1.00 UAH = 9.00 UAH
+    Expenses:Cafes
+
+2026-10-01 * Coffee Point
+    ; monobank_id: b
+    Assets:Monobank:Black-UAH  -2.00 UAH = 7.00 UAH
+    Expenses:Cafes
+
+2026-10-02 ! Coffee Point
+    ; monobank_id: c
+    Assets:Monobank:Black-UAH  -0.50 UAH
+    Expenses:Uncategorized
+
+"
+        );
+    }
+
+    #[test]
+    fn beancount_opens_accounts_and_asserts_complete_days() {
+        assert_eq!(
+            written(&history(), &[], &AccountMap::default(), Flavor::Beancount),
+            "\
+2026-10-01 open Assets:Monobank:Black-UAH
+2026-10-01 open Equity:Opening-Balances
+2026-10-01 open Expenses:Cafes
+2026-10-02 open Expenses:Uncategorized
+
+2026-10-01 * \"Opening balance\"
+  Assets:Monobank:Black-UAH  10.00 UAH
+  Equity:Opening-Balances
+
+2026-10-01 * \"Coffee Point\"
+  monobank_id: \"a\"
+  Assets:Monobank:Black-UAH  -1.00 UAH
+  Expenses:Cafes
+
+2026-10-01 * \"Coffee Point\"
+  monobank_id: \"b\"
+  Assets:Monobank:Black-UAH  -2.00 UAH
+  Expenses:Cafes
+
+2026-10-02 ! \"Coffee Point\"
+  monobank_id: \"c\"
+  Assets:Monobank:Black-UAH  -0.50 UAH
+  Expenses:Uncategorized
+
+2026-10-02 balance Assets:Monobank:Black-UAH  7.00 UAH
+
+"
+        );
+    }
+
+    #[test]
+    fn later_exports_assert_the_days_they_completed() {
+        let mut previous = history();
+        previous[1].hold = false;
+        let mut late = item("e", "black", at(1, 12), -20, 680);
+        late.counter_name = Some("Cafe \"Lviv\"".to_string());
+        let items = [item("d", "black", at(3, 9), -50, 600), late];
+        assert_eq!(
+            written(&items, &previous, &AccountMap::default(), Flavor::Beancount),
+            "\
+2026-10-01 * \"Cafe \\\"Lviv\\\"\" \"Coffee Point\"
+  monobank_id: \"e\"
+  Assets:Monobank:Black-UAH  -0.20 UAH
+  Expenses:Cafes
+
+2026-10-03 * \"Coffee Point\"
+  monobank_id: \"d\"
+  Assets:Monobank:Black-UAH  -0.50 UAH
+  Expenses:Cafes
+
+2026-10-02 balance Assets:Monobank:Black-UAH  6.80 UAH
+2026-10-03 balance Assets:Monobank:Black-UAH  6.50 UAH
+
+"
+        );
+    }
+
+    #[test]
+    fn line_breaks_are_replaced_and_unknown_currencies_skipped() {
+        let mut accounts = accounts();
+        accounts.get_mut("usd").unwrap().currency_code = 4;
+        let mut multiline = item("a", "black", at(1, 8), -100, 900);
+        multiline.description = "Coffee\nPoint".to_string();
+        multiline.comment = Some("latte\r\nlarge".to_string());
+        let items = [multiline, item("b", "usd", at(1, 9), -100, 0)];
+        for flavor in [Flavor::Ledger, Flavor::Beancount] {
+            let mut out = Vec::new();
+            let map = AccountMap::default();
+            assert_eq!(
+                write(&items, &[], &accounts, &map, flavor, &mut out).unwrap(),
+                1
+            );
+            let out = String::from_utf8(out).unwrap();
+            assert!(out.contains("Coffee Point"));
+            assert!(out.contains("latte  large"));
+            assert!(!out.contains("monobank_id: b") && !out.contains("monobank_id: \"b\""));
+        }
+    }
+
+    #[test]
+    fn account_map_overrides_the_defaults() {
+        let map = AccountMap::from_toml(
+            r#"
+            expense_account = "Expenses:Misc"
+
+            [accounts]
+            usd = "Assets:Bank:Dollars"
+
+            [categories]
+            cafes = "Expenses:Food:Coffee"
+            "#,
+        )
+        .unwrap();
+        let mut uncategorized = item("b", "black", at(1, 9), -100, 0);
+        uncategorized.category = Some("home & garden".to_string());
+        let mut refund = item("c", "black", at(1, 10), 100, 100);
+        refund.category = None;
+        let items = [item("a", "usd", at(1, 8), -100, 0), uncategorized, refund];
+        let out = written(&items, &items, &map, Flavor::Ledger);
+        assert!(out
+            .contains("    Assets:Bank:Dollars  -1.00 USD = 0.00 USD\n    Expenses:Food:Coffee\n"));
+        assert!(out.contains(
+            "    Assets:Monobank:Black-UAH  -1.00 UAH = 0.00 UAH\n    Expenses:Home-garden\n"
+        ));
+        assert!(out.contains(
+            "    Assets:Monobank:Black-UAH  1.00 UAH = 1.00 UAH\n    Income:Uncategorized\n"
+        ));
+        assert!(AccountMap::from_toml("income = \"Income:Other\"").is_err());
+    }
+}
@@ -0,0 +1,279 @@
+use crate::export::{self, Accounts};
+use crate::money::{self, Money};
+use crate::{models, utils};
+use serde_with::chrono::{DateTime, NaiveDateTime};
+use std::io::{self, Write};
+
+/// Monobank's MFO, which OFX calls the routing number.
+const BANK_ID: &str = "322001";
+
+/// OFX keeps names short, longer ones are cut after escaping.
+const NAME_LENGTH: usize = 32;
+
+/// Writes an OFX 2.2 document with a bank statement per account that has items.
+///
+/// `FITID` is the statement item id, so apps that dedupe on it can import overlapping
+/// exports. The ledger balance is the account's balance as of its last sync.
+pub fn write(
+    items: &[models::StatementItem],
+    accounts: &Accounts,
+    mut out: impl Write,
+) -> io::Result<usize> {
+    writeln!(
+        out,
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="no"?>"#
+    )?;
+    writeln!(
+        out,
+        r#"<?OFX OFXHEADER="200" VERSION="220" SECURITY="NONE" OLDFILEUID="NONE" NEWFILEUID="NONE"?>"#
+    )?;
+    writeln!(out, "<OFX>")?;
+    writeln!(out, "<SIGNONMSGSRSV1><SONRS>")?;
+    writeln!(
+        out,
+        "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>"
+    )?;
+    writeln!(
+        out,
+        "<DTSERVER>{}</DTSERVER>",
+        timestamp(utils::current_timestamp())
+    )?;
+    writeln!(out, "<LANGUAGE>UKR</LANGUAGE>")?;
+    writeln!(out, "</SONRS></SIGNONMSGSRSV1>")?;
+    writeln!(out, "<BANKMSGSRSV1>")?;
+    for (account_id, items) in export::by_account(items) {
+        let account = accounts.get(account_id);
+        let currency = export::account_currency(accounts, items[0]);
+        writeln!(out, "<STMTTRNRS>")?;
+        writeln!(out, "<TRNUID>{}</TRNUID>", escape(account_id))?;
+        writeln!(
+            out,
+            "<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>"
+        )?;
+        writeln!(out, "<STMTRS>")?;
+        writeln!(out, "<CURDEF>{}</CURDEF>", money::alpha_code(currency))?;
+        writeln!(out, "<BANKACCTFROM>")?;
+        writeln!(out, "<BANKID>{BANK_ID}</BANKID>")?;
+        let acct_id = account
+            .and_then(|account| account.iban.as_deref())
+            .unwrap_or(account_id);
+        writeln!(out, "<ACCTID>{}</ACCTID>", escape(acct_id))?;
+        writeln!(out, "<ACCTTYPE>{}</ACCTTYPE>", account_type(account))?;
+        writeln!(out, "</BANKACCTFROM>")?;
+        writeln!(out, "<BANKTRANLIST>")?;
+        writeln!(out, "<DTSTART>{}</DTSTART>", datetime(items[0].time))?;
+        writeln!(
+            out,
+            "<DTEND>{}</DTEND>",
+            datetime(items[items.len() - 1].time)
+        )?;
+        for item in &items {
+            let kind = if item.amount < 0 { "DEBIT" } else { "CREDIT" };
+            writeln!(out, "<STMTTRN>")?;
+            writeln!(out, "<TRNTYPE>{kind}</TRNTYPE>")?;
+            writeln!(out, "<DTPOSTED>{}</DTPOSTED>", datetime(item.time))?;
+            writeln!(
+                out,
+                "<TRNAMT>{}</TRNAMT>",
+                item.amount_money(currency).decimal()
+            )?;
+            writeln!(out, "<FITID>{}</FITID>", escape(&item.id))?;
+            let name = item.counter_name.as_deref().unwrap_or(&item.description);
+            writeln!(out, "<NAME>{}</NAME>", short_name(name))?;
+            let memo = [Some(item.description.as_str()), item.comment.as_deref()]
+                .into_iter()
+                .flatten()
+                .filter(|text| *text != name)
+                .collect::<Vec<&str>>()
+                .join(" / ");
+            if !memo.is_empty() {
+                writeln!(out, "<MEMO>{}</MEMO>", escape(&memo))?;
+            }
+            writeln!(out, "</STMTTRN>")?;
+        }
+        writeln!(out, "</BANKTRANLIST>")?;
+        // Accounts that are gone only have the balance after their last item
+        let (balance, as_of) = match account {
+            Some(account) => (
+                account.balance,
+                account.last_sync_at.unwrap_or(items[items.len() - 1].time),
+            ),
+            None => (items[items.len() - 1].balance, items[items.len() - 1].time),
+        };
+        writeln!(out, "<LEDGERBAL>")?;
+        writeln!(
+            out,
+            "<BALAMT>{}</BALAMT>",
+            Money::new(balance, currency).decimal()
+        )?;
+        writeln!(out, "<DTASOF>{}</DTASOF>", datetime(as_of))?;
+        writeln!(out, "</LEDGERBAL>")?;
+        writeln!(out, "</STMTRS>")?;
+        writeln!(out, "</STMTTRNRS>")?;
+    }
+    writeln!(out, "</BANKMSGSRSV1>")?;
+    writeln!(out, "</OFX>")?;
+    out.flush()?;
+    Ok(items.len())
+}
+
+/// Cards with a credit limit are credit lines, jars savings and everything else checking.
+fn account_type(account: Option<&models::AccountBalance>) -> &'static str {
+    match account {
+        Some(account) if account.credit_limit > 0 => "CREDITLINE",
+        Some(account) if account.account_type == "jar" => "SAVINGS",
+        _ => "CHECKING",
+    }
+}
+
+/// `name` escaped and cut to `NAME_LENGTH` characters, never in the middle of an entity.
+fn short_name(name: &str) -> String {
+    let mut short = String::new();
+    let mut length = 0;
+    for c in name.chars() {
+        let escaped = escape(c.encode_utf8(&mut [0; 4]));
+        length += escaped.chars().count();
+        if length > NAME_LENGTH {
+            break;
+        }
+        short.push_str(&escaped);
+    }
+    short
+}
+
+/// Stored times are local to `TIMEZONE`, OFX gets them in UTC.
+fn datetime(time: NaiveDateTime) -> String {
+    timestamp(utils::timestamp_from(time))
+}
+
+fn timestamp(timestamp: u32) -> String {
+    DateTime::from_timestamp(timestamp as i64, 0)
+        .expect("Failed to convert timestamp to DateTime")
+        .format("%Y%m%d%H%M%S.000[0:GMT]")
+        .to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::fixtures::{accounts, at, item};
+
+    #[test]
+    fn statements_are_written_per_account() {
+        let mut accounts = accounts();
+        let black = accounts.get_mut("black").unwrap();
+        black.iban = Some("UA213223130000026007233566001".to_string());
+        black.balance = 123_456;
+        black.last_sync_at = Some(at(3, 0));
+        black.credit_limit = 5_000_000;
+        let mut paid = item("a", "black", at(1, 8), -4_150, 1_000);
+        paid.counter_name = Some("Tom & Jerry's <Cafe>".to_string());
+        paid.comment = Some("latte".to_string());
+        let items = [
+            paid,
+            item("b", "gone", at(2, 9), 2_000, 5_000),
+            item("c", "black", at(2, 10), 500, 1_500),
+        ];
+        let mut out = Vec::new();
+        assert_eq!(write(&items, &accounts, &mut out).unwrap(), 3);
+        let out = String::from_utf8(out).unwrap();
+
+        let statements = &out[out.find("<STMTTRNRS>").unwrap()..];
+        assert_eq!(
+            statements,
+            format!(
+                "\
+<STMTTRNRS>
+<TRNUID>black</TRNUID>
+<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>
+<STMTRS>
+<CURDEF>UAH</CURDEF>
+<BANKACCTFROM>
+<BANKID>322001</BANKID>
+<ACCTID>UA213223130000026007233566001</ACCTID>
+<ACCTTYPE>CREDITLINE</ACCTTYPE>
+</BANKACCTFROM>
+<BANKTRANLIST>
+<DTSTART>{a}</DTSTART>
+<DTEND>{c}</DTEND>
+<STMTTRN>
+<TRNTYPE>DEBIT</TRNTYPE>
+<DTPOSTED>{a}</DTPOSTED>
+<TRNAMT>-41.50</TRNAMT>
+<FITID>a</FITID>
+<NAME>Tom &amp; Jerry's &lt;Cafe&gt;</NAME>
+<MEMO>Coffee Point / latte</MEMO>
+</STMTTRN>
+<STMTTRN>
+<TRNTYPE>CREDIT</TRNTYPE>
+<DTPOSTED>{c}</DTPOSTED>
+<TRNAMT>5.00</TRNAMT>
+<FITID>c</FITID>
+<NAME>Coffee Point</NAME>
+</STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL>
+<BALAMT>1234.56</BALAMT>
+<DTASOF>{synced}</DTASOF>
+</LEDGERBAL>
+</STMTRS>
+</STMTTRNRS>
+<STMTTRNRS>
+<TRNUID>gone</TRNUID>
+<STATUS><CODE>0</CODE><SEVERITY>INFO</SEVERITY></STATUS>
+<STMTRS>
+<CURDEF>UAH</CURDEF>
+<BANKACCTFROM>
+<BANKID>322001</BANKID>
+<ACCTID>gone</ACCTID>
+<ACCTTYPE>CHECKING</ACCTTYPE>
+</BANKACCTFROM>
+<BANKTRANLIST>
+<DTSTART>{b}</DTSTART>
+<DTEND>{b}</DTEND>
+<STMTTRN>
+<TRNTYPE>CREDIT</TRNTYPE>
+<DTPOSTED>{b}</DTPOSTED>
+<TRNAMT>20.00</TRNAMT>
+<FITID>b</FITID>
+<NAME>Coffee Point</NAME>
+</STMTTRN>
+</BANKTRANLIST>
+<LEDGERBAL>
+<BALAMT>50.00</BALAMT>
+<DTASOF>{b}</DTASOF>
+</LEDGERBAL>
+</STMTRS>
+</STMTTRNRS>
+</BANKMSGSRSV1>
+</OFX>
+",
+                a = datetime(at(1, 8)),
+                b = datetime(at(2, 9)),
+                c = datetime(at(2, 10)),
+                synced = datetime(at(3, 0)),
+            )
+        );
+    }
+
+    #[test]
+    fn names_are_cut_after_escaping_between_entities() {
+        assert_eq!(short_name("Tom & Jerry"), "Tom &amp; Jerry");
+        let name = format!("{}&{}", "a".repeat(29), "b".repeat(10));
+        assert_eq!(short_name(&name), "a".repeat(29));
+        let name = format!("{}&{}", "a".repeat(27), "b".repeat(10));
+        assert_eq!(short_name(&name), format!("{}&amp;", "a".repeat(27)));
+        assert_eq!(short_name(&"є".repeat(40)).chars().count(), NAME_LENGTH);
+    }
+
+    #[test]
+    fn timestamps_are_utc() {
+        assert_eq!(timestamp(1_790_000_000), "20260921141320.000[0:GMT]");
+    }
+}
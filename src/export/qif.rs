@@ -0,0 +1,150 @@
+use crate::export::{self, Accounts};
+use crate::models;
+use crate::money::Money;
+use std::io::{self, Write};
+
+/// Writes a QIF bank register per account that has items, each preceded by an
+/// `!Account` block naming the account by IBAN so apps import into the right one.
+///
+/// The accounts are listed with their balances up front between `!Option:AutoSwitch`
+/// and `!Clear:AutoSwitch`, which GnuCash and Quicken need to take a multi-account file
+/// apart. QIF has no transaction ids, so unlike OFX re-importing the same items
+/// duplicates them.
+pub fn write(
+    items: &[models::StatementItem],
+    accounts: &Accounts,
+    mut out: impl Write,
+) -> io::Result<usize> {
+    let groups = export::by_account(items);
+    let name = |account_id: &str| {
+        accounts
+            .get(account_id)
+            .and_then(|account| account.iban.clone())
+            .unwrap_or_else(|| account_id.to_string())
+    };
+    writeln!(out, "!Option:AutoSwitch")?;
+    for (account_id, items) in &groups {
+        writeln!(out, "!Account")?;
+        writeln!(out, "N{}", line(&name(account_id)))?;
+        writeln!(out, "TBank")?;
+        if let Some(account) = accounts.get(*account_id) {
+            let currency = export::account_currency(accounts, items[0]);
+            writeln!(out, "${}", Money::new(account.balance, currency).decimal())?;
+        }
+        writeln!(out, "^")?;
+    }
+    writeln!(out, "!Clear:AutoSwitch")?;
+    for (account_id, items) in groups {
+        let currency = export::account_currency(accounts, items[0]);
+        writeln!(out, "!Account")?;
+        writeln!(out, "N{}", line(&name(account_id)))?;
+        writeln!(out, "TBank")?;
+        writeln!(out, "^")?;
+        writeln!(out, "!Type:Bank")?;
+        for item in &items {
+            writeln!(out, "D{}", item.time.format("%m/%d/%Y"))?;
+            writeln!(out, "T{}", item.amount_money(currency).decimal())?;
+            if !item.hold {
+                writeln!(out, "CX")?;
+            }
+            let payee = item.counter_name.as_deref().unwrap_or(&item.description);
+            writeln!(out, "P{}", line(payee))?;
+            let memo = [Some(item.description.as_str()), item.comment.as_deref()]
+                .into_iter()
+                .flatten()
+                .filter(|text| *text != payee)
+                .collect::<Vec<&str>>()
+                .join(" / ");
+            if !memo.is_empty() {
+                writeln!(out, "M{}", line(&memo))?;
+            }
+            if let Some(category) = &item.category {
+                writeln!(out, "L{}", line(category))?;
+            }
+            writeln!(out, "^")?;
+        }
+    }
+    out.flush()?;
+    Ok(items.len())
+}
+
+/// QIF fields end at the line break.
+fn line(text: &str) -> String {
+    text.replace(['\r', '\n'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::fixtures::{accounts, at, item};
+
+    #[test]
+    fn registers_are_written_per_account() {
+        let mut accounts = accounts();
+        let black = accounts.get_mut("black").unwrap();
+        black.iban = Some("UA213223130000026007233566001".to_string());
+        black.balance = 123_456;
+        let mut paid = item("a", "black", at(1, 8), -4_150, 1_000);
+        paid.counter_name = Some("Cafe".to_string());
+        paid.comment = Some("latte\nlarge".to_string());
+        let mut held = item("b", "gone", at(2, 9), -2_000, 5_000);
+        held.hold = true;
+        held.category = None;
+        let items = [paid, held, item("c", "usd", at(12, 10), 500, 1_500)];
+        let mut out = Vec::new();
+        assert_eq!(write(&items, &accounts, &mut out).unwrap(), 3);
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "\
+!Option:AutoSwitch
+!Account
+NUA213223130000026007233566001
+TBank
+$1234.56
+^
+!Account
+Ngone
+TBank
+^
+!Account
+Nusd
+TBank
+$0.00
+^
+!Clear:AutoSwitch
+!Account
+NUA213223130000026007233566001
+TBank
+^
+!Type:Bank
+D10/01/2026
+T-41.50
+CX
+PCafe
+MCoffee Point / latte large
+Lcafes
+^
+!Account
+Ngone
+TBank
+^
+!Type:Bank
+D10/02/2026
+T-20.00
+PCoffee Point
+^
+!Account
+Nusd
+TBank
+^
+!Type:Bank
+D10/12/2026
+T5.00
+CX
+PCoffee Point
+Lcafes
+^
+"
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use crate::db_types::{Database, DatabasePool};
+#[cfg(feature = "sqlite")]
+use crate::{config, utils};
+use sqlx::pool::PoolConnection;
+use std::fmt;
+
+/// Exit code when another instance holds the lock, `EX_TEMPFAIL` so cron wrappers can tell it apart.
+pub const EXIT_LOCKED: i32 = 75;
+
+#[cfg(any(feature = "sqlite", feature = "mysql"))]
+const LOCK_NAME: &str = "monobank-sync";
+#[cfg(feature = "postgres")]
+const ADVISORY_LOCK_KEY: i64 = 0x6d6f6e6f;
+
+#[derive(Debug)]
+pub enum LockError {
+    /// Another instance holds the lock, described for the log.
+    Held(String),
+    Database(sqlx::Error),
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LockError::Held(holder) => write!(f, "another instance holds the lock ({holder})"),
+            LockError::Database(e) => write!(f, "failed to take the lock: {e}"),
+        }
+    }
+}
+
+impl From<sqlx::Error> for LockError {
+    fn from(e: sqlx::Error) -> Self {
+        LockError::Database(e)
+    }
+}
+
+/// Keeps a second syncer away from the same database.
+///
+/// PostgreSQL and MySQL use a session-level advisory lock on a dedicated connection,
+/// which the server releases by itself when the process dies. SQLite has no such thing,
+/// so a row in `instance_lock` is kept fresh by a heartbeat and taken over once it is
+/// older than `LOCK_STALE_AFTER`.
+pub struct InstanceLock {
+    connection: PoolConnection<Database>,
+    #[cfg(feature = "sqlite")]
+    holder: String,
+    #[cfg(feature = "sqlite")]
+    heartbeat: tokio::task::JoinHandle<()>,
+}
+
+impl InstanceLock {
+    #[cfg(feature = "postgres")]
+    pub async fn acquire(pool: &DatabasePool) -> Result<Self, LockError> {
+        let mut connection = pool.acquire().await?;
+        let locked = sqlx::query_scalar!(
+            r#"SELECT pg_try_advisory_lock($1) AS "locked!""#,
+            ADVISORY_LOCK_KEY,
+        )
+        .fetch_one(&mut *connection)
+        .await?;
+        if !locked {
+            let holder = sqlx::query!(
+                r#"SELECT a.pid AS "pid!", a.backend_start, a.application_name
+                FROM pg_locks l JOIN pg_stat_activity a ON a.pid = l.pid
+                WHERE l.locktype = 'advisory' AND l.granted AND l.classid = 0 AND l.objid::bigint = $1"#,
+                ADVISORY_LOCK_KEY,
+            )
+            .fetch_optional(&mut *connection)
+            .await?;
+            let holder = match holder {
+                Some(h) => format!(
+                    "PostgreSQL backend pid {}, {} connected since {}",
+                    h.pid,
+                    h.application_name.unwrap_or_default(),
+                    h.backend_start.map(|t| t.to_string()).unwrap_or_default(),
+                ),
+                None => "unknown PostgreSQL session".to_string(),
+            };
+            return Err(LockError::Held(holder));
+        }
+        tracing::debug!("Acquired the instance lock");
+        Ok(Self { connection })
+    }
+
+    #[cfg(feature = "mysql")]
+    pub async fn acquire(pool: &DatabasePool) -> Result<Self, LockError> {
+        let mut connection = pool.acquire().await?;
+        let locked = sqlx::query_scalar!("SELECT GET_LOCK(?, 0) AS locked", LOCK_NAME)
+            .fetch_one(&mut *connection)
+            .await?;
+        if locked != Some(1) {
+            let holder = sqlx::query_scalar!("SELECT IS_USED_LOCK(?) AS holder", LOCK_NAME)
+                .fetch_one(&mut *connection)
+                .await?;
+            let holder = match holder {
+                Some(id) => format!("MySQL connection id {id}"),
+                None => "unknown MySQL session".to_string(),
+            };
+            return Err(LockError::Held(holder));
+        }
+        tracing::debug!("Acquired the instance lock");
+        Ok(Self { connection })
+    }
+
+    #[cfg(feature = "sqlite")]
+    pub async fn acquire(pool: &DatabasePool) -> Result<Self, LockError> {
+        let mut connection = pool.acquire().await?;
+        let holder = format!("pid {} #{:08x}", std::process::id(), rand::random::<u32>());
+        let stale_after = config::get_lock_stale_after();
+        let now = utils::datetime_from(utils::current_timestamp());
+
+        let existing = sqlx::query!(
+            "SELECT holder, acquired_at, heartbeat_at FROM instance_lock WHERE name = ?",
+            LOCK_NAME,
+        )
+        .fetch_optional(&mut *connection)
+        .await?;
+        if let Some(existing) = &existing {
+            let idle = (now - existing.heartbeat_at).to_std().unwrap_or_default();
+            if idle > stale_after {
+                tracing::warn!(
+                    holder = existing.holder,
+                    last_heartbeat = %existing.heartbeat_at,
+                    "Taking over a stale instance lock",
+                );
+                sqlx::query!(
+                    "DELETE FROM instance_lock WHERE name = ? AND holder = ?",
+                    LOCK_NAME,
+                    existing.holder,
+                )
+                .execute(&mut *connection)
+                .await?;
+            }
+        }
+
+        let inserted = sqlx::query!(
+            "INSERT OR IGNORE INTO instance_lock (name, holder, acquired_at, heartbeat_at) VALUES (?, ?, ?, ?)",
+            LOCK_NAME,
+            holder,
+            now,
+            now,
+        )
+        .execute(&mut *connection)
+        .await?;
+        if inserted.rows_affected() == 0 {
+            let current = sqlx::query!(
+                "SELECT holder, acquired_at, heartbeat_at FROM instance_lock WHERE name = ?",
+                LOCK_NAME,
+            )
+            .fetch_optional(&mut *connection)
+            .await?;
+            let holder = match current {
+                Some(c) => format!(
+                    "{} since {}, last heartbeat {}",
+                    c.holder, c.acquired_at, c.heartbeat_at
+                ),
+                None => "released meanwhile, try again".to_string(),
+            };
+            return Err(LockError::Held(holder));
+        }
+        tracing::debug!(holder, "Acquired the instance lock");
+
+        let heartbeat = tokio::spawn(heartbeat(pool.clone(), holder.clone(), stale_after / 4));
+        Ok(Self {
+            connection,
+            holder,
+            heartbeat,
+        })
+    }
+
+    pub async fn release(mut self) {
+        #[cfg(feature = "postgres")]
+        let result = sqlx::query_scalar!("SELECT pg_advisory_unlock($1)", ADVISORY_LOCK_KEY)
+            .fetch_one(&mut *self.connection)
+            .await;
+
+        #[cfg(feature = "mysql")]
+        let result = sqlx::query_scalar!("SELECT RELEASE_LOCK(?)", LOCK_NAME)
+            .fetch_one(&mut *self.connection)
+            .await;
+
+        #[cfg(feature = "sqlite")]
+        let result = {
+            self.heartbeat.abort();
+            sqlx::query!(
+                "DELETE FROM instance_lock WHERE name = ? AND holder = ?",
+                LOCK_NAME,
+                self.holder,
+            )
+            .execute(&mut *self.connection)
+            .await
+        };
+
+        match result {
+            Ok(_) => tracing::debug!("Released the instance lock"),
+            Err(e) => tracing::warn!("Failed to release the instance lock: {:?}", e),
+        }
+    }
+}
+
+#[cfg(feature = "sqlite")]
+async fn heartbeat(pool: DatabasePool, holder: String, every: std::time::Duration) {
+    let mut ticker = tokio::time::interval(every.max(std::time::Duration::from_secs(1)));
+    ticker.tick().await;
+    loop {
+        ticker.tick().await;
+        let now = utils::datetime_from(utils::current_timestamp());
+        let result = sqlx::query!(
+            "UPDATE instance_lock SET heartbeat_at = ? WHERE name = ? AND holder = ?",
+            now,
+            LOCK_NAME,
+            holder,
+        )
+        .execute(&pool)
+        .await;
+        match result {
+            Ok(r) if r.rows_affected() == 0 => {
+                tracing::warn!("Lost the instance lock to another instance");
+            }
+            Ok(_) => {}
+            Err(e) => tracing::warn!("Failed to refresh the instance lock: {:?}", e),
+        }
+    }
+}
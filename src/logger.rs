@@ -1,9 +1,13 @@
 use tracing::Level;
 use tracing_subscriber::FmtSubscriber;
 
-pub fn initialize_logging() {
-    let subscriber = FmtSubscriber::builder()
-        .with_max_level(Level::INFO)
-        .finish();
-    tracing::subscriber::set_global_default(subscriber).expect("setting trace logger failed");
+/// Logs to stdout, or to stderr when stdout carries exported data.
+pub fn initialize_logging(to_stderr: bool) {
+    let builder = FmtSubscriber::builder().with_max_level(Level::INFO);
+    let result = if to_stderr {
+        tracing::subscriber::set_global_default(builder.with_writer(std::io::stderr).finish())
+    } else {
+        tracing::subscriber::set_global_default(builder.finish())
+    };
+    result.expect("setting trace logger failed");
 }
@@ -1,142 +1,294 @@
+mod annotations;
 mod api;
+mod backfill;
+mod categories;
+mod cli;
 mod config;
+mod conversion;
 mod crud;
+mod daemon;
 mod db;
 mod db_types;
+mod dry_run;
+mod export;
+mod instance_lock;
 mod logger;
 mod models;
+mod money;
+#[cfg(feature = "parquet")]
+mod parquet_sink;
+mod rates;
+mod reset;
+mod schedule;
 mod schema;
+mod shutdown;
+mod sink;
+mod sync;
+#[cfg(test)]
+mod testing;
+mod transfers;
 mod utils;
-use reqwest::blocking::Client;
-use std::time::{SystemTime, UNIX_EPOCH};
+mod verify;
+mod webhook;
+use clap::Parser;
+use reqwest::Client;
+use serde_with::chrono::Days;
+use std::sync::Arc;
 
 #[tokio::main]
 async fn main() {
-    logger::initialize_logging();
+    let cli = cli::Cli::parse();
     config::load_env();
+    let command = cli
+        .command
+        .unwrap_or_else(|| cli::Command::Sync(Default::default()));
+    logger::initialize_logging(command.writes_to_stdout());
+    let categorizer = if command.categorizes_items() {
+        match categories::Categorizer::load() {
+            Ok(categorizer) => categorizer,
+            Err(e) => {
+                tracing::error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        Default::default()
+    };
+    let sinks = if command.writes_to_sinks() {
+        match sink::Sinks::from_config() {
+            Ok(sinks) => sinks,
+            Err(e) => {
+                tracing::error!("{}", e);
+                std::process::exit(1);
+            }
+        }
+    } else {
+        Default::default()
+    };
     let time_floor = utils::datetime_from(config::get_sync_start_timestamp());
     let pool = db::initialize(&config::get_database_url()).await;
-    let client = Client::new();
-    let tokens = config::get_multiple_monobank_tokens();
-    let now = SystemTime::now()
-        .duration_since(UNIX_EPOCH)
-        .expect("System time is before UNIX epoch")
-        .as_secs() as u32;
-    for token in &tokens {
-        let raw_client_info = match api::fetch_client_info(&client, token) {
-            Ok(info) => info,
-            Err(e) => {
-                tracing::error!("Failed to fetch client info: {:?}", e);
-                continue;
-            }
-        };
-        let client_info = models::ClientInfo {
-            client_id: raw_client_info.client_id,
-            name: raw_client_info.name,
-            token: token.to_string(),
-        };
-        if let Err(e) = crud::insert_client_info(&pool, client_info.clone()).await {
-            tracing::warn!("Failed to insert client info: {:?}", e);
+    let lock = if command.takes_sync_lock() {
+        match instance_lock::InstanceLock::acquire(&pool).await {
+            Ok(lock) => Some(lock),
+            Err(instance_lock::LockError::Held(holder)) => {
+                tracing::error!(
+                    "Another instance is already syncing this database ({}), exiting",
+                    holder
+                );
+                std::process::exit(instance_lock::EXIT_LOCKED);
+            }
+            Err(e) => panic!("Failed to take the instance lock: {e}"),
         }
-        let relevant_accounts = raw_client_info
-            .accounts
-            .clone()
-            .iter()
-            .filter(|&ac| config::get_all_allowed_card_types().contains(&ac.account_type))
-            .cloned()
-            .collect::<Vec<schema::Account>>();
-        for raw_account in relevant_accounts {
-            let account_id = raw_account.id.clone();
-            let last_sync_time = crud::get_last_sync_time(&pool, account_id.clone())
-                .await
-                .ok()
-                .flatten()
-                .unwrap_or(time_floor);
-            let account = models::Account {
-                id: raw_account.id,
-                client_id: client_info.client_id.clone(),
-                send_id: raw_account.send_id,
-                iban: raw_account.iban,
-                account_type: raw_account.account_type,
-                currency_code: raw_account.currency_code,
-                balance: raw_account.balance,
-                credit_limit: raw_account.credit_limit,
-                cashback_type: raw_account.cashback_type,
-                last_sync_at: Some(last_sync_time),
-            };
-            if let Err(e) = crud::insert_account(&pool, account.clone()).await {
-                tracing::warn!("Failed to insert account {}: {:?}", account.id, e);
-            }
-            let card_statements = api::FetchingStatementsIterator {
-                client: &client,
-                token: token.to_string(),
-                account_id: account.id.clone(),
-                last_success_time: (last_sync_time.and_utc().timestamp() - 1) as u32,
-                end_time: now,
-                wait_length_sec: api::WAIT_TIME_SEC,
-                wait_jitter_sec: api::WAIT_JITTER_SEC,
-            };
-            for statement_response in card_statements {
-                let raw_statements: Vec<schema::StatementItem>;
-                match statement_response {
-                    Ok((timestamp, s)) => {
-                        raw_statements = s;
-                        let last_success = utils::datetime_from(timestamp);
-                        if let Err(e) = crud::update_last_sync_time(
-                            &pool,
-                            account.id.clone(),
-                            Some(last_success),
-                        )
-                        .await
-                        {
-                            tracing::warn!("Failed to update sync time: {:?}", e);
-                        }
-                    }
-                    Err((timestamp, e)) => {
-                        tracing::error!("Error fetching statements: {:?}", e);
-                        let last_success = utils::datetime_from(timestamp);
-                        if let Err(e) = crud::update_last_sync_time(
-                            &pool,
-                            account.id.clone(),
-                            Some(last_success),
-                        )
-                        .await
-                        {
-                            tracing::warn!("Failed to update sync time after error: {:?}", e);
-                        }
-                        break;
-                    }
-                }
-                let statements = raw_statements
-                    .into_iter()
-                    .map(|s| models::StatementItem {
-                        id: s.id,
-                        account_id: account.id.clone(),
-                        time: utils::datetime_from_utc_to_tz(s.time),
-                        description: s.description,
-                        mcc: s.mcc,
-                        original_mcc: s.original_mcc,
-                        hold: s.hold,
-                        amount: s.amount,
-                        operation_amount: s.operation_amount,
-                        currency_code: s.currency_code,
-                        commission_rate: s.commission_rate,
-                        cashback_amount: s.cashback_amount,
-                        balance: s.balance,
-                        comment: s.comment,
-                        receipt_id: s.receipt_id,
-                        invoice_id: s.invoice_id,
-                        counter_iban: s.counter_iban,
-                        counter_name: s.counter_name,
-                        counter_edrpou: s.counter_edrpou,
-                    })
-                    .collect::<Vec<models::StatementItem>>();
-                for statement_item in statements {
-                    if let Err(e) = crud::insert_statement_item(&pool, statement_item).await {
-                        tracing::warn!("Failed to insert statement: {:?}", e);
-                    }
+    } else {
+        None
+    };
+    let syncer = sync::Syncer::new(pool, Client::new(), categorizer, sinks);
+    if command.stops_gracefully() {
+        syncer.shutdown.listen_for_signals();
+    }
+    match command {
+        cli::Command::Sync(args) => {
+            let tokens = config::get_multiple_monobank_tokens();
+            if args.dry_run {
+                dry_run::run(&syncer, &tokens, time_floor, args.fetch).await;
+            } else {
+                syncer.sync_all(&tokens, time_floor).await;
+            }
+        }
+        cli::Command::Daemon { schedule } => {
+            daemon::run(&syncer, schedule).await;
+        }
+        cli::Command::Serve {
+            url,
+            listen,
+            schedule,
+        } => {
+            let url = url
+                .or_else(config::get_webhook_url)
+                .expect("WEBHOOK_URL or --url must be set");
+            let listen = listen.unwrap_or_else(|| {
+                config::get_webhook_listen_addr()
+                    .parse()
+                    .expect("WEBHOOK_LISTEN_ADDR must be an address like 0.0.0.0:8080")
+            });
+            webhook::serve(Arc::new(syncer), &url, listen, schedule).await;
+        }
+        cli::Command::Webhook { action } => match action {
+            cli::WebhookAction::Set { url } => {
+                let url = url
+                    .or_else(config::get_webhook_url)
+                    .expect("WEBHOOK_URL or --url must be set");
+                webhook::set(&syncer, &url).await;
+            }
+            cli::WebhookAction::Show => webhook::show(&syncer).await,
+            cli::WebhookAction::Clear => webhook::clear(&syncer).await,
+        },
+        cli::Command::Annotate { action } => match action {
+            cli::AnnotateAction::Note { item_id, note } => {
+                annotations::apply(&syncer.pool, &item_id, annotations::Edit::SetNote(note)).await
+            }
+            cli::AnnotateAction::ClearNote { item_id } => {
+                annotations::apply(&syncer.pool, &item_id, annotations::Edit::ClearNote).await
+            }
+            cli::AnnotateAction::Tag { item_id, tags } => {
+                annotations::apply(&syncer.pool, &item_id, annotations::Edit::AddTags(tags)).await
+            }
+            cli::AnnotateAction::Untag { item_id, tags } => {
+                annotations::apply(&syncer.pool, &item_id, annotations::Edit::RemoveTags(tags))
+                    .await
+            }
+            cli::AnnotateAction::Show { item_id } => {
+                annotations::show(&syncer.pool, &item_id).await
+            }
+        },
+        cli::Command::Recategorize { account } => {
+            let categorizer = syncer
+                .categorizer
+                .read()
+                .expect("Categorizer lock poisoned")
+                .clone();
+            categories::recategorize(&syncer.pool, &categorizer, account.as_deref()).await;
+        }
+        cli::Command::MatchTransfers { since } => {
+            let since = since.map(|day| utils::datetime_from(utils::day_start_timestamp(day)));
+            transfers::detect(&syncer.pool, since).await;
+        }
+        cli::Command::RecomputeBase => {
+            conversion::update_base_amounts(&syncer.pool, config::get_base_currency(), true).await;
+        }
+        cli::Command::Verify { account } => {
+            if !verify::run(&syncer.pool, account.as_deref()).await {
+                std::process::exit(1);
+            }
+        }
+        cli::Command::Export { format } => match format {
+            cli::ExportFormat::Csv(args) => {
+                let options = export::csv::CsvOptions {
+                    columns: args.columns,
+                    amounts: args.amounts,
+                    decimal_separator: args.decimal_separator,
+                    thousands_separator: args.thousands_separator,
+                    delimiter: args.delimiter,
+                };
+                let selection = args.selection.to_selection();
+                export::run(
+                    &syncer.pool,
+                    &selection,
+                    args.selection.output.as_deref(),
+                    |items, accounts, out| export::csv::write(items, accounts, &options, out),
+                )
+                .await;
+            }
+            cli::ExportFormat::Ofx(args) => {
+                export::run(
+                    &syncer.pool,
+                    &args.to_selection(),
+                    args.output.as_deref(),
+                    export::ofx::write,
+                )
+                .await;
+            }
+            cli::ExportFormat::Qif(args) => {
+                export::run(
+                    &syncer.pool,
+                    &args.to_selection(),
+                    args.output.as_deref(),
+                    export::qif::write,
+                )
+                .await;
+            }
+            cli::ExportFormat::Firefly(args) => {
+                export_budget(&syncer.pool, export::budget::App::Firefly, args).await
+            }
+            cli::ExportFormat::Actual(args) => {
+                export_budget(&syncer.pool, export::budget::App::Actual, args).await
+            }
+            cli::ExportFormat::Ynab(args) => {
+                export_budget(&syncer.pool, export::budget::App::Ynab, args).await
+            }
+            cli::ExportFormat::Ledger(args) => {
+                export_journal(&syncer.pool, export::journal::Flavor::Ledger, args).await
+            }
+            cli::ExportFormat::Beancount(args) => {
+                export_journal(&syncer.pool, export::journal::Flavor::Beancount, args).await
+            }
+            #[cfg(feature = "parquet")]
+            cli::ExportFormat::Parquet { dir } => {
+                let dir = dir
+                    .or_else(|| config::get_parquet_output_dir().map(Into::into))
+                    .expect("PARQUET_OUTPUT_DIR or --dir must be set");
+                let sink = parquet_sink::ParquetSink::new(dir);
+                match sink.export_statement_items(&syncer.pool).await {
+                    Ok(items) => tracing::info!(items, "Exported statement items to Parquet"),
+                    Err(e) => tracing::error!("Failed to export statement items: {:?}", e),
                 }
             }
+        },
+        cli::Command::Backfill {
+            account,
+            from,
+            to,
+            since,
+        } => {
+            let from = from.map(utils::day_start_timestamp);
+            let to = to.map(|day| utils::day_start_timestamp(day + Days::new(1)) - 1);
+            let since = since.map(utils::day_start_timestamp);
+            backfill::run(&syncer, account, from, to, since, time_floor).await;
         }
+        cli::Command::Reset {
+            account,
+            since,
+            delete_items,
+            force,
+            dry_run,
+        } => {
+            let since = since
+                .map(|day| utils::datetime_from(utils::day_start_timestamp(day)))
+                .unwrap_or(time_floor);
+            reset::run(&syncer.pool, &account, since, delete_items, force, dry_run).await;
+        }
+    }
+    if let Some(lock) = lock {
+        lock.release().await;
     }
 }
+
+async fn export_journal(
+    pool: &db_types::DatabasePool,
+    flavor: export::journal::Flavor,
+    args: cli::JournalArgs,
+) {
+    let path = args
+        .accounts_file
+        .or_else(config::get_journal_accounts_file);
+    let map = match export::journal::AccountMap::load(path.as_deref()) {
+        Ok(map) => map,
+        Err(e) => {
+            tracing::error!("{}", e);
+            return;
+        }
+    };
+    export::run_journal(
+        pool,
+        &args.selection.to_selection(),
+        flavor,
+        &map,
+        args.selection.output.as_deref(),
+        args.incremental,
+    )
+    .await;
+}
+
+async fn export_budget(
+    pool: &db_types::DatabasePool,
+    app: export::budget::App,
+    args: cli::ExportSelection,
+) {
+    export::run(
+        pool,
+        &args.to_selection(),
+        args.output.as_deref(),
+        |items, accounts, out| export::budget::write(items, accounts, app, out),
+    )
+    .await;
+}
@@ -1,3 +1,5 @@
+use crate::money::Money;
+use serde::Serialize;
 use serde_with::chrono::NaiveDateTime;
 
 #[derive(Debug, Clone, sqlx::FromRow)]
@@ -12,7 +14,7 @@ pub struct ClientInfo {
     pub token: String,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Account {
     pub id: String,
     pub client_id: String,
@@ -26,7 +28,13 @@ pub struct Account {
     pub last_sync_at: Option<NaiveDateTime>,
 }
 
-#[derive(Debug, Clone)]
+impl Account {
+    pub fn balance_money(&self) -> Money {
+        Money::new(self.balance, self.currency_code)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct StatementItem {
     pub id: String,
     pub account_id: String,
@@ -47,4 +55,170 @@ pub struct StatementItem {
     pub counter_edrpou: Option<String>,
     pub counter_iban: Option<String>,
     pub counter_name: Option<String>,
+    pub category: Option<String>,
+}
+
+/// A tag assigned to a statement item by a categorization rule.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StatementItemTag {
+    pub statement_item_id: String,
+    pub tag: String,
+}
+
+/// A user's note on a statement item, kept apart from the synced data.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TransactionAnnotation {
+    pub note: String,
+    pub updated_at: NaiveDateTime,
+}
+
+/// A statement item that is not part of a matched internal transfer yet.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct TransferCandidate {
+    pub id: String,
+    pub account_id: String,
+    pub client_id: String,
+    pub time: NaiveDateTime,
+    pub description: String,
+    pub amount: i64,
+    pub operation_amount: i64,
+    pub currency_code: i64,
+    pub account_currency: i64,
+    pub counter_iban: Option<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AccountIban {
+    pub client_id: String,
+    pub iban: String,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct AccountBalance {
+    pub id: String,
+    pub account_type: String,
+    pub balance: i64,
+    pub credit_limit: i64,
+    pub currency_code: i64,
+    pub iban: Option<String>,
+    pub last_sync_at: Option<NaiveDateTime>,
+}
+
+/// `StatementItem` as read back, with integer columns widened to what every backend decodes.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct StatementItemRow {
+    pub id: String,
+    pub account_id: String,
+    pub time: NaiveDateTime,
+    pub description: String,
+    pub mcc: i64,
+    pub original_mcc: i64,
+    pub hold: bool,
+    pub amount: i64,
+    pub operation_amount: i64,
+    pub currency_code: i64,
+    pub commission_rate: i64,
+    pub cashback_amount: i64,
+    pub balance: i64,
+    pub comment: Option<String>,
+    pub receipt_id: Option<String>,
+    pub invoice_id: Option<String>,
+    pub counter_edrpou: Option<String>,
+    pub counter_iban: Option<String>,
+    pub counter_name: Option<String>,
+    pub category: Option<String>,
+}
+
+impl From<StatementItemRow> for StatementItem {
+    fn from(row: StatementItemRow) -> Self {
+        Self {
+            id: row.id,
+            account_id: row.account_id,
+            time: row.time,
+            description: row.description,
+            mcc: row.mcc as u32,
+            original_mcc: row.original_mcc as u32,
+            hold: row.hold,
+            amount: row.amount,
+            operation_amount: row.operation_amount,
+            currency_code: row.currency_code as u32,
+            commission_rate: row.commission_rate,
+            cashback_amount: row.cashback_amount,
+            balance: row.balance,
+            comment: row.comment,
+            receipt_id: row.receipt_id,
+            invoice_id: row.invoice_id,
+            counter_edrpou: row.counter_edrpou,
+            counter_iban: row.counter_iban,
+            counter_name: row.counter_name,
+            category: row.category,
+        }
+    }
+}
+
+impl StatementItem {
+    pub fn operation_money(&self) -> Money {
+        Money::new(self.operation_amount, self.currency_code)
+    }
+
+    /// `amount` and `balance` are in the account's currency, which the item doesn't carry.
+    pub fn amount_money(&self, account_currency: u32) -> Money {
+        Money::new(self.amount, account_currency)
+    }
+
+    pub fn balance_money(&self, account_currency: u32) -> Money {
+        Money::new(self.balance, account_currency)
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct SyncRun {
+    pub id: i64,
+    pub token_fingerprint: String,
+    pub started_at: NaiveDateTime,
+    pub finished_at: Option<NaiveDateTime>,
+    pub accounts_touched: i64,
+    pub windows_fetched: i64,
+    pub items_fetched: i64,
+    pub items_inserted: i64,
+    pub errors: Option<String>,
+}
+
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct WindowRange {
+    pub window_start: NaiveDateTime,
+    pub window_end: Option<NaiveDateTime>,
+}
+
+#[derive(Debug, Clone)]
+pub struct SyncWindow {
+    pub run_id: i64,
+    pub account_id: String,
+    pub window_start: NaiveDateTime,
+    pub window_end: Option<NaiveDateTime>,
+    pub status: String,
+    pub items_fetched: i64,
+    pub items_inserted: i64,
+    pub error: Option<String>,
+    pub fetched_at: NaiveDateTime,
+}
+
+#[derive(Debug, Clone)]
+pub struct CurrencyRate {
+    pub currency_code_a: u32,
+    pub currency_code_b: u32,
+    pub date: NaiveDateTime,
+    pub rate_buy: Option<f64>,
+    pub rate_sell: Option<f64>,
+    pub rate_cross: Option<f64>,
+    pub fetched_at: NaiveDateTime,
+}
+
+/// What it takes to convert a statement item's `amount` from its account's currency.
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct BaseAmountSource {
+    pub id: String,
+    pub time: NaiveDateTime,
+    pub amount: i64,
+    pub account_currency: i64,
 }
@@ -0,0 +1,338 @@
+use std::fmt;
+
+/// ISO 4217 currency, the same list the `currencies` table is seeded with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Currency {
+    pub numeric_code: u32,
+    pub alpha_code: &'static str,
+    /// Digits after the decimal point, amounts are stored as integers of these.
+    pub minor_units: u8,
+    pub symbol: Option<&'static str>,
+}
+
+impl Currency {
+    const fn new(
+        numeric_code: u32,
+        alpha_code: &'static str,
+        minor_units: u8,
+        symbol: Option<&'static str>,
+    ) -> Self {
+        Self {
+            numeric_code,
+            alpha_code,
+            minor_units,
+            symbol,
+        }
+    }
+
+    pub fn from_numeric(numeric_code: u32) -> Option<&'static Currency> {
+        CURRENCIES
+            .binary_search_by_key(&numeric_code, |c| c.numeric_code)
+            .ok()
+            .map(|index| &CURRENCIES[index])
+    }
+
+    pub fn from_alpha(alpha_code: &str) -> Option<&'static Currency> {
+        CURRENCIES
+            .iter()
+            .find(|c| c.alpha_code.eq_ignore_ascii_case(alpha_code))
+    }
+}
+
+/// Minor units of `numeric_code`, assuming cents for codes missing from the list.
+pub fn minor_units(numeric_code: u32) -> u8 {
+    Currency::from_numeric(numeric_code).map_or(2, |c| c.minor_units)
+}
+
+/// The alpha code of `numeric_code`, or the number itself for codes missing from the list.
+pub fn alpha_code(numeric_code: u32) -> String {
+    Currency::from_numeric(numeric_code)
+        .map_or_else(|| numeric_code.to_string(), |c| c.alpha_code.to_string())
+}
+
+/// Reads a currency given either as `UAH` or as `980`.
+pub fn parse_currency_code(value: &str) -> Option<u32> {
+    let value = value.trim();
+    match value.parse::<u32>() {
+        Ok(numeric_code) => Some(numeric_code),
+        Err(_) => Currency::from_alpha(value).map(|c| c.numeric_code),
+    }
+}
+
+/// An amount in minor units of a currency, displayed like `-123.45 UAH`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Money {
+    pub minor: i64,
+    pub currency_code: u32,
+}
+
+impl Money {
+    pub fn new(minor: i64, currency_code: u32) -> Self {
+        Self {
+            minor,
+            currency_code,
+        }
+    }
+
+    pub fn currency(&self) -> Option<&'static Currency> {
+        Currency::from_numeric(self.currency_code)
+    }
+
+    /// The amount in whole units without the currency, e.g. `-123.45`.
+    pub fn decimal(&self) -> String {
+        self.localized('.', None)
+    }
+
+    /// Like `decimal`, with the given separators, e.g. `-1 234,56`.
+    pub fn localized(&self, decimal_separator: char, thousands_separator: Option<char>) -> String {
+        let digits = minor_units(self.currency_code) as u32;
+        let sign = if self.minor < 0 { "-" } else { "" };
+        let abs = self.minor.unsigned_abs();
+        let scale = 10u64.pow(digits);
+        let mut whole = (abs / scale).to_string();
+        if let Some(separator) = thousands_separator {
+            let mut grouped = String::with_capacity(whole.len() * 4 / 3);
+            for (i, digit) in whole.chars().enumerate() {
+                if i > 0 && (whole.len() - i).is_multiple_of(3) {
+                    grouped.push(separator);
+                }
+                grouped.push(digit);
+            }
+            whole = grouped;
+        }
+        if digits == 0 {
+            return format!("{sign}{whole}");
+        }
+        format!(
+            "{sign}{whole}{decimal_separator}{:0width$}",
+            abs % scale,
+            width = digits as usize
+        )
+    }
+}
+
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.currency() {
+            Some(currency) => write!(f, "{} {}", self.decimal(), currency.alpha_code),
+            None => write!(f, "{} {}", self.decimal(), self.currency_code),
+        }
+    }
+}
+
+/// Sorted by numeric code.
+pub static CURRENCIES: &[Currency] = &[
+    Currency::new(8, "ALL", 2, Some("L")),
+    Currency::new(12, "DZD", 2, None),
+    Currency::new(32, "ARS", 2, Some("$")),
+    Currency::new(36, "AUD", 2, Some("A$")),
+    Currency::new(44, "BSD", 2, Some("$")),
+    Currency::new(48, "BHD", 3, None),
+    Currency::new(50, "BDT", 2, Some("৳")),
+    Currency::new(51, "AMD", 2, Some("֏")),
+    Currency::new(52, "BBD", 2, Some("$")),
+    Currency::new(60, "BMD", 2, Some("$")),
+    Currency::new(64, "BTN", 2, None),
+    Currency::new(68, "BOB", 2, Some("Bs")),
+    Currency::new(72, "BWP", 2, Some("P")),
+    Currency::new(84, "BZD", 2, Some("$")),
+    Currency::new(90, "SBD", 2, Some("$")),
+    Currency::new(96, "BND", 2, Some("$")),
+    Currency::new(104, "MMK", 2, Some("K")),
+    Currency::new(108, "BIF", 0, None),
+    Currency::new(116, "KHR", 2, Some("៛")),
+    Currency::new(124, "CAD", 2, Some("C$")),
+    Currency::new(132, "CVE", 2, None),
+    Currency::new(136, "KYD", 2, Some("$")),
+    Currency::new(144, "LKR", 2, Some("Rs")),
+    Currency::new(152, "CLP", 0, Some("$")),
+    Currency::new(156, "CNY", 2, Some("¥")),
+    Currency::new(170, "COP", 2, Some("$")),
+    Currency::new(174, "KMF", 0, None),
+    Currency::new(188, "CRC", 2, Some("₡")),
+    Currency::new(192, "CUP", 2, Some("$")),
+    Currency::new(203, "CZK", 2, Some("Kč")),
+    Currency::new(208, "DKK", 2, Some("kr")),
+    Currency::new(214, "DOP", 2, Some("$")),
+    Currency::new(230, "ETB", 2, None),
+    Currency::new(232, "ERN", 2, None),
+    Currency::new(238, "FKP", 2, Some("£")),
+    Currency::new(242, "FJD", 2, Some("$")),
+    Currency::new(262, "DJF", 0, None),
+    Currency::new(270, "GMD", 2, None),
+    Currency::new(292, "GIP", 2, Some("£")),
+    Currency::new(320, "GTQ", 2, Some("Q")),
+    Currency::new(324, "GNF", 0, None),
+    Currency::new(328, "GYD", 2, Some("$")),
+    Currency::new(332, "HTG", 2, None),
+    Currency::new(340, "HNL", 2, Some("L")),
+    Currency::new(344, "HKD", 2, Some("HK$")),
+    Currency::new(348, "HUF", 2, Some("Ft")),
+    Currency::new(352, "ISK", 0, Some("kr")),
+    Currency::new(356, "INR", 2, Some("₹")),
+    Currency::new(360, "IDR", 2, Some("Rp")),
+    Currency::new(364, "IRR", 2, Some("﷼")),
+    Currency::new(368, "IQD", 3, None),
+    Currency::new(376, "ILS", 2, Some("₪")),
+    Currency::new(388, "JMD", 2, Some("$")),
+    Currency::new(392, "JPY", 0, Some("¥")),
+    Currency::new(398, "KZT", 2, Some("₸")),
+    Currency::new(400, "JOD", 3, None),
+    Currency::new(404, "KES", 2, None),
+    Currency::new(408, "KPW", 2, Some("₩")),
+    Currency::new(410, "KRW", 0, Some("₩")),
+    Currency::new(414, "KWD", 3, None),
+    Currency::new(417, "KGS", 2, Some("с")),
+    Currency::new(418, "LAK", 2, Some("₭")),
+    Currency::new(422, "LBP", 2, None),
+    Currency::new(426, "LSL", 2, None),
+    Currency::new(430, "LRD", 2, Some("$")),
+    Currency::new(434, "LYD", 3, None),
+    Currency::new(446, "MOP", 2, None),
+    Currency::new(454, "MWK", 2, None),
+    Currency::new(458, "MYR", 2, Some("RM")),
+    Currency::new(462, "MVR", 2, None),
+    Currency::new(480, "MUR", 2, Some("Rs")),
+    Currency::new(484, "MXN", 2, Some("$")),
+    Currency::new(496, "MNT", 2, Some("₮")),
+    Currency::new(498, "MDL", 2, Some("L")),
+    Currency::new(504, "MAD", 2, None),
+    Currency::new(512, "OMR", 3, None),
+    Currency::new(516, "NAD", 2, Some("$")),
+    Currency::new(524, "NPR", 2, Some("Rs")),
+    Currency::new(532, "ANG", 2, Some("ƒ")),
+    Currency::new(533, "AWG", 2, Some("ƒ")),
+    Currency::new(548, "VUV", 0, None),
+    Currency::new(554, "NZD", 2, Some("NZ$")),
+    Currency::new(558, "NIO", 2, None),
+    Currency::new(566, "NGN", 2, Some("₦")),
+    Currency::new(578, "NOK", 2, Some("kr")),
+    Currency::new(586, "PKR", 2, Some("Rs")),
+    Currency::new(590, "PAB", 2, None),
+    Currency::new(598, "PGK", 2, None),
+    Currency::new(600, "PYG", 0, Some("₲")),
+    Currency::new(604, "PEN", 2, Some("S/")),
+    Currency::new(608, "PHP", 2, Some("₱")),
+    Currency::new(634, "QAR", 2, None),
+    Currency::new(643, "RUB", 2, Some("₽")),
+    Currency::new(646, "RWF", 0, None),
+    Currency::new(654, "SHP", 2, Some("£")),
+    Currency::new(682, "SAR", 2, None),
+    Currency::new(690, "SCR", 2, None),
+    Currency::new(702, "SGD", 2, Some("S$")),
+    Currency::new(704, "VND", 0, Some("₫")),
+    Currency::new(706, "SOS", 2, None),
+    Currency::new(710, "ZAR", 2, Some("R")),
+    Currency::new(728, "SSP", 2, None),
+    Currency::new(748, "SZL", 2, None),
+    Currency::new(752, "SEK", 2, Some("kr")),
+    Currency::new(756, "CHF", 2, Some("CHF")),
+    Currency::new(760, "SYP", 2, None),
+    Currency::new(764, "THB", 2, Some("฿")),
+    Currency::new(776, "TOP", 2, None),
+    Currency::new(780, "TTD", 2, Some("$")),
+    Currency::new(784, "AED", 2, Some("د.إ")),
+    Currency::new(788, "TND", 3, None),
+    Currency::new(800, "UGX", 0, None),
+    Currency::new(807, "MKD", 2, Some("ден")),
+    Currency::new(818, "EGP", 2, Some("E£")),
+    Currency::new(826, "GBP", 2, Some("£")),
+    Currency::new(834, "TZS", 2, None),
+    Currency::new(840, "USD", 2, Some("$")),
+    Currency::new(858, "UYU", 2, Some("$")),
+    Currency::new(860, "UZS", 2, None),
+    Currency::new(882, "WST", 2, None),
+    Currency::new(886, "YER", 2, None),
+    Currency::new(901, "TWD", 2, Some("NT$")),
+    Currency::new(924, "ZWG", 2, None),
+    Currency::new(925, "SLE", 2, None),
+    Currency::new(928, "VES", 2, None),
+    Currency::new(929, "MRU", 2, None),
+    Currency::new(930, "STN", 2, None),
+    Currency::new(933, "BYN", 2, Some("Br")),
+    Currency::new(934, "TMT", 2, None),
+    Currency::new(936, "GHS", 2, Some("₵")),
+    Currency::new(938, "SDG", 2, None),
+    Currency::new(941, "RSD", 2, Some("дин")),
+    Currency::new(943, "MZN", 2, None),
+    Currency::new(944, "AZN", 2, Some("₼")),
+    Currency::new(946, "RON", 2, Some("lei")),
+    Currency::new(949, "TRY", 2, Some("₺")),
+    Currency::new(950, "XAF", 0, None),
+    Currency::new(951, "XCD", 2, Some("$")),
+    Currency::new(952, "XOF", 0, None),
+    Currency::new(953, "XPF", 0, None),
+    Currency::new(967, "ZMW", 2, None),
+    Currency::new(968, "SRD", 2, Some("$")),
+    Currency::new(969, "MGA", 2, None),
+    Currency::new(971, "AFN", 2, Some("؋")),
+    Currency::new(972, "TJS", 2, None),
+    Currency::new(973, "AOA", 2, Some("Kz")),
+    Currency::new(975, "BGN", 2, Some("лв")),
+    Currency::new(976, "CDF", 2, None),
+    Currency::new(977, "BAM", 2, Some("KM")),
+    Currency::new(978, "EUR", 2, Some("€")),
+    Currency::new(980, "UAH", 2, Some("₴")),
+    Currency::new(981, "GEL", 2, Some("₾")),
+    Currency::new(985, "PLN", 2, Some("zł")),
+    Currency::new(986, "BRL", 2, Some("R$")),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn currencies_are_sorted_for_lookups() {
+        assert!(CURRENCIES
+            .windows(2)
+            .all(|pair| pair[0].numeric_code < pair[1].numeric_code));
+        assert_eq!(Currency::from_numeric(980).unwrap().alpha_code, "UAH");
+        assert_eq!(Currency::from_numeric(999), None);
+        assert_eq!(alpha_code(980), "UAH");
+        assert_eq!(alpha_code(999), "999");
+        assert_eq!(parse_currency_code("usd"), Some(840));
+        assert_eq!(parse_currency_code(" 978 "), Some(978));
+    }
+
+    #[test]
+    fn localized_groups_thousands_and_pads_minor_units() {
+        assert_eq!(
+            Money::new(-123_456_705, 980).localized(',', Some(' ')),
+            "-1 234 567,05"
+        );
+        assert_eq!(
+            Money::new(123_456, 980).localized(',', Some(' ')),
+            "1 234,56"
+        );
+        assert_eq!(Money::new(99_999, 980).localized('.', Some(',')), "999.99");
+        assert_eq!(
+            Money::new(1_234_567, 392).localized('.', Some(',')),
+            "1,234,567"
+        );
+        assert_eq!(
+            Money::new(-1_234_567, 414).localized(',', Some('.')),
+            "-1.234,567"
+        );
+    }
+
+    #[test]
+    fn decimal_pads_minor_units() {
+        assert_eq!(Money::new(-123_456_705, 980).decimal(), "-1234567.05");
+        assert_eq!(Money::new(5, 840).decimal(), "0.05");
+        assert_eq!(Money::new(-5, 840).decimal(), "-0.05");
+    }
+
+    #[test]
+    fn decimal_follows_the_minor_units_of_the_currency() {
+        assert_eq!(Money::new(1_234_567, 392).decimal(), "1234567");
+        assert_eq!(Money::new(-1_234_567, 414).decimal(), "-1234.567");
+        assert_eq!(Money::new(100, 999).decimal(), "1.00");
+    }
+
+    #[test]
+    fn display_appends_the_alpha_code() {
+        assert_eq!(Money::new(-12_345, 980).to_string(), "-123.45 UAH");
+        assert_eq!(Money::new(100, 999).to_string(), "1.00 999");
+    }
+}
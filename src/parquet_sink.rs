@@ -0,0 +1,278 @@
+use crate::db_types::DatabasePool;
+use crate::sink::{Sink, SinkError};
+use crate::{crud, models, utils};
+use arrow_array::{
+    ArrayRef, BooleanArray, Int64Array, RecordBatch, StringArray, TimestampMicrosecondArray,
+    UInt32Array,
+};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::errors::ParquetError;
+use parquet::file::properties::WriterProperties;
+use std::collections::{BTreeMap, HashSet};
+use std::fs::{self, File, OpenOptions};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Writes synced data as hive-partitioned Parquet files that DuckDB, Polars and
+/// friends can read directly, e.g. `read_parquet('out/statement_items/**/*.parquet', hive_partitioning = true)`.
+///
+/// Accounts are written as they are synced. Statement items are exported from the
+/// database instead, see `export_statement_items`, so the dataset keeps its own
+/// cursor and catches up on anything it missed. Part files are never replaced:
+/// exports in the same second get a numbered name next to the existing file instead.
+#[derive(Debug, Clone)]
+pub struct ParquetSink {
+    root: PathBuf,
+}
+
+/// `journal_exports.format` of the items written to a dataset, whose directory is the target.
+const EXPORT_FORMAT: &str = "parquet";
+
+impl ParquetSink {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Writes every stored statement item the dataset doesn't have yet, one part file
+    /// per account and month, and returns how many were written.
+    ///
+    /// Items are recorded in `journal_exports` once their part file is written, so the
+    /// first export writes the whole stored history and a partition that failed is
+    /// written again by the next one.
+    pub async fn export_statement_items(&self, pool: &DatabasePool) -> Result<usize, sqlx::Error> {
+        let target = self.root.display().to_string();
+        let exported = crud::get_exported_item_ids(pool, EXPORT_FORMAT, &target)
+            .await?
+            .into_iter()
+            .collect::<HashSet<String>>();
+        let items = crud::get_statement_items(pool, None, None).await?;
+        let mut partitions: BTreeMap<(&str, String), Vec<&models::StatementItem>> = BTreeMap::new();
+        for item in items.iter().filter(|item| !exported.contains(&item.id)) {
+            partitions
+                .entry((&item.account_id, item.time.format("%Y-%m").to_string()))
+                .or_default()
+                .push(item);
+        }
+        let now = utils::current_timestamp();
+        let exported_at = utils::datetime_from(now);
+        let mut written = 0;
+        for ((account_id, month), partition) in partitions {
+            let dir = self
+                .root
+                .join("statement_items")
+                .join(format!("account_id={account_id}"))
+                .join(format!("month={month}"));
+            let result = statement_items_batch(&partition)
+                .and_then(|batch| write_batch(&dir, &format!("part-{now}"), batch));
+            if let Err(e) = result {
+                tracing::error!(
+                    account_id,
+                    month,
+                    "Failed to write statement items, the next export retries them: {:?}",
+                    e
+                );
+                continue;
+            }
+            for item in &partition {
+                crud::insert_exported_item(pool, EXPORT_FORMAT, &target, &item.id, exported_at)
+                    .await?;
+            }
+            written += partition.len();
+        }
+        Ok(written)
+    }
+}
+
+impl Sink for ParquetSink {
+    fn name(&self) -> &'static str {
+        "parquet"
+    }
+
+    fn write_account(&self, account: &models::Account, synced_at: u32) -> Result<(), SinkError> {
+        let dir = self
+            .root
+            .join("accounts")
+            .join(format!("account_id={}", account.id));
+        let batch = RecordBatch::try_new(
+            accounts_schema(),
+            vec![
+                string_column([Some(account.id.as_str())]),
+                string_column([Some(account.client_id.as_str())]),
+                string_column([Some(account.send_id.as_str())]),
+                Arc::new(Int64Array::from(vec![account.balance])),
+                Arc::new(Int64Array::from(vec![account.credit_limit])),
+                string_column([Some(account.account_type.as_str())]),
+                Arc::new(UInt32Array::from(vec![account.currency_code])),
+                string_column([account.cashback_type.as_deref()]),
+                string_column([account.iban.as_deref()]),
+                Arc::new(TimestampMicrosecondArray::from(vec![account
+                    .last_sync_at
+                    .map(|t| t.and_utc().timestamp_micros())])),
+            ],
+        )?;
+        write_batch(&dir, &format!("part-{synced_at}"), batch)?;
+        Ok(())
+    }
+
+    /// Nothing to do, the items are exported from the database after the run.
+    fn write_statement_items(
+        &self,
+        _account_id: &str,
+        _window_end: u32,
+        _items: &[models::StatementItem],
+    ) -> Result<(), SinkError> {
+        Ok(())
+    }
+}
+
+fn accounts_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("client_id", DataType::Utf8, false),
+        Field::new("send_id", DataType::Utf8, false),
+        Field::new("balance", DataType::Int64, false),
+        Field::new("credit_limit", DataType::Int64, false),
+        Field::new("account_type", DataType::Utf8, false),
+        Field::new("currency_code", DataType::UInt32, false),
+        Field::new("cashback_type", DataType::Utf8, true),
+        Field::new("iban", DataType::Utf8, true),
+        Field::new(
+            "last_sync_at",
+            DataType::Timestamp(TimeUnit::Microsecond, None),
+            true,
+        ),
+    ]))
+}
+
+fn statement_items_schema() -> Arc<Schema> {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("account_id", DataType::Utf8, false),
+        Field::new(
+            "time",
+            DataType::Timestamp(TimeUnit::Microsecond, None),
+            false,
+        ),
+        Field::new("description", DataType::Utf8, false),
+        Field::new("mcc", DataType::UInt32, false),
+        Field::new("original_mcc", DataType::UInt32, false),
+        Field::new("hold", DataType::Boolean, false),
+        Field::new("amount", DataType::Int64, false),
+        Field::new("operation_amount", DataType::Int64, false),
+        Field::new("currency_code", DataType::UInt32, false),
+        Field::new("commission_rate", DataType::Int64, false),
+        Field::new("cashback_amount", DataType::Int64, false),
+        Field::new("balance", DataType::Int64, false),
+        Field::new("comment", DataType::Utf8, true),
+        Field::new("receipt_id", DataType::Utf8, true),
+        Field::new("invoice_id", DataType::Utf8, true),
+        Field::new("counter_edrpou", DataType::Utf8, true),
+        Field::new("counter_iban", DataType::Utf8, true),
+        Field::new("counter_name", DataType::Utf8, true),
+        Field::new("category", DataType::Utf8, true),
+    ]))
+}
+
+fn statement_items_batch(items: &[&models::StatementItem]) -> Result<RecordBatch, ParquetError> {
+    let columns: Vec<ArrayRef> = vec![
+        string_column(items.iter().map(|s| Some(s.id.as_str()))),
+        string_column(items.iter().map(|s| Some(s.account_id.as_str()))),
+        Arc::new(TimestampMicrosecondArray::from_iter_values(
+            items.iter().map(|s| s.time.and_utc().timestamp_micros()),
+        )),
+        string_column(items.iter().map(|s| Some(s.description.as_str()))),
+        Arc::new(UInt32Array::from_iter_values(items.iter().map(|s| s.mcc))),
+        Arc::new(UInt32Array::from_iter_values(
+            items.iter().map(|s| s.original_mcc),
+        )),
+        Arc::new(BooleanArray::from(
+            items.iter().map(|s| s.hold).collect::<Vec<bool>>(),
+        )),
+        Arc::new(Int64Array::from_iter_values(items.iter().map(|s| s.amount))),
+        Arc::new(Int64Array::from_iter_values(
+            items.iter().map(|s| s.operation_amount),
+        )),
+        Arc::new(UInt32Array::from_iter_values(
+            items.iter().map(|s| s.currency_code),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            items.iter().map(|s| s.commission_rate),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            items.iter().map(|s| s.cashback_amount),
+        )),
+        Arc::new(Int64Array::from_iter_values(
+            items.iter().map(|s| s.balance),
+        )),
+        string_column(items.iter().map(|s| s.comment.as_deref())),
+        string_column(items.iter().map(|s| s.receipt_id.as_deref())),
+        string_column(items.iter().map(|s| s.invoice_id.as_deref())),
+        string_column(items.iter().map(|s| s.counter_edrpou.as_deref())),
+        string_column(items.iter().map(|s| s.counter_iban.as_deref())),
+        string_column(items.iter().map(|s| s.counter_name.as_deref())),
+        string_column(items.iter().map(|s| s.category.as_deref())),
+    ];
+    Ok(RecordBatch::try_new(statement_items_schema(), columns)?)
+}
+
+fn string_column<'a>(values: impl IntoIterator<Item = Option<&'a str>>) -> ArrayRef {
+    Arc::new(values.into_iter().collect::<StringArray>())
+}
+
+/// Writes `<stem>.parquet`, or `<stem>-1.parquet` and so on when that is taken.
+fn write_batch(dir: &Path, stem: &str, batch: RecordBatch) -> Result<(), ParquetError> {
+    fs::create_dir_all(dir)?;
+    let (path, file) = create_new_part(dir, stem)?;
+    tracing::debug!(path = %path.display(), rows = batch.num_rows(), "Writing parquet file...");
+    let props = WriterProperties::builder()
+        .set_compression(Compression::SNAPPY)
+        .build();
+    let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+    writer.write(&batch)?;
+    writer.close()?;
+    Ok(())
+}
+
+/// `create_new` makes taking a name atomic, so concurrent writers never share a file.
+fn create_new_part(dir: &Path, stem: &str) -> io::Result<(PathBuf, File)> {
+    for attempt in 0.. {
+        let file_name = match attempt {
+            0 => format!("{stem}.parquet"),
+            n => format!("{stem}-{n}.parquet"),
+        };
+        let path = dir.join(file_name);
+        match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => return Ok((path, file)),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    unreachable!("ran out of part file names")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn part_names_never_replace_existing_files() {
+        let dir = std::env::temp_dir().join(format!("parquet-sink-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let names = (0..3)
+            .map(|_| create_new_part(&dir, "part-100").unwrap().0)
+            .map(|path| path.file_name().unwrap().to_string_lossy().into_owned())
+            .collect::<Vec<String>>();
+        fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(
+            names,
+            [
+                "part-100.parquet",
+                "part-100-1.parquet",
+                "part-100-2.parquet"
+            ]
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use crate::db_types::DatabasePool;
+use crate::{api, crud, models, utils};
+use reqwest::Client;
+
+/// Monobank refreshes the public rates about every five minutes, asking more often only risks a 429.
+const MIN_FETCH_INTERVAL_SEC: u32 = 5 * 60;
+
+/// Stores the current public exchange rates, keeping every previously seen rate as history.
+/// Fetches are logged in `currency_rate_fetches`, which the fetch interval is measured from.
+pub async fn sync(pool: &DatabasePool, client: &Client) {
+    let now = utils::current_timestamp();
+    match crud::get_last_rates_fetch(pool).await {
+        Ok(Some(last))
+            if now.saturating_sub(utils::timestamp_from(last)) < MIN_FETCH_INTERVAL_SEC =>
+        {
+            tracing::info!("Currency rates were fetched recently, skipping");
+            return;
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to get last currency rates fetch: {:?}", e),
+    }
+    let raw_rates = match api::fetch_currency_rates(client).await {
+        Ok(rates) => rates,
+        Err(e) => {
+            tracing::warn!("Failed to fetch currency rates: {:?}", e);
+            return;
+        }
+    };
+    let fetched_at = utils::datetime_from(now);
+    let pairs = raw_rates.len();
+    let mut inserted = 0;
+    for raw in raw_rates {
+        let rate = models::CurrencyRate {
+            currency_code_a: raw.currency_code_a,
+            currency_code_b: raw.currency_code_b,
+            date: utils::datetime_from_utc_to_tz(raw.date),
+            rate_buy: raw.rate_buy,
+            rate_sell: raw.rate_sell,
+            rate_cross: raw.rate_cross,
+            fetched_at,
+        };
+        match crud::insert_currency_rate(pool, rate).await {
+            Ok(true) => inserted += 1,
+            Ok(false) => {}
+            Err(e) => tracing::warn!("Failed to insert currency rate: {:?}", e),
+        }
+    }
+    tracing::info!(pairs, new_rates = inserted, "Currency rates stored");
+    if let Err(e) = crud::insert_currency_rate_fetch(pool, fetched_at, pairs as u32, inserted).await
+    {
+        tracing::warn!("Failed to record currency rates fetch: {:?}", e);
+    }
+}
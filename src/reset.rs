@@ -0,0 +1,71 @@
+use crate::db_types::DatabasePool;
+use crate::{api, crud, sync, utils};
+use serde_with::chrono::NaiveDateTime;
+
+/// Moves an account's sync cursor back to `since`, so the next sync re-fetches everything after it.
+/// Moving it forward would skip the items in between, so that takes `force`.
+pub async fn run(
+    pool: &DatabasePool,
+    account_id: &str,
+    since: NaiveDateTime,
+    delete_items: bool,
+    force: bool,
+    dry_run: bool,
+) {
+    let current = match crud::get_last_sync_time(pool, account_id.to_string()).await {
+        Ok(Some(last_sync_at)) => last_sync_at,
+        Ok(None) => {
+            tracing::error!(account_id, "Unknown or never synced account");
+            return;
+        }
+        Err(e) => {
+            tracing::error!("Failed to get sync time for {}: {:?}", account_id, e);
+            return;
+        }
+    };
+    if since >= current && !force {
+        tracing::error!(
+            account_id,
+            current_cursor = %current,
+            new_cursor = %since,
+            "The new cursor is not before the current one, so the next sync would skip the items in between; pass --force to move it anyway",
+        );
+        return;
+    }
+    let items = match crud::count_statement_items_since(pool, account_id, since).await {
+        Ok(count) => count,
+        Err(e) => {
+            tracing::error!("Failed to count statement items: {:?}", e);
+            return;
+        }
+    };
+    let requests =
+        api::plan_windows(utils::timestamp_from(since) - 1, utils::current_timestamp()).len();
+    tracing::info!(
+        account_id,
+        current_cursor = %current,
+        new_cursor = %since,
+        stored_items = items,
+        delete_items,
+        requests,
+        estimated_minutes = sync::eta_minutes(requests),
+        "Reset plan",
+    );
+    if dry_run {
+        tracing::info!("Dry run, nothing changed");
+        return;
+    }
+    if delete_items {
+        match crud::delete_statement_items_since(pool, account_id, since).await {
+            Ok(deleted) => tracing::info!(deleted, "Deleted statement items"),
+            Err(e) => {
+                tracing::error!("Failed to delete statement items: {:?}", e);
+                return;
+            }
+        }
+    }
+    match crud::update_last_sync_time(pool, account_id.to_string(), Some(since)).await {
+        Ok(()) => tracing::info!(account_id, "Sync cursor reset, run a sync to re-fetch"),
+        Err(e) => tracing::error!("Failed to reset sync cursor: {:?}", e),
+    }
+}
@@ -0,0 +1,107 @@
+use crate::config;
+use serde_with::chrono::{DateTime, Utc};
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+/// When the daemon syncs: a fixed interval between run starts (`6h`, `30m`, `1h 30m`)
+/// or a cron expression evaluated in the configured timezone (`0 3 * * *`).
+#[derive(Debug, Clone)]
+pub enum Schedule {
+    Interval(Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+impl FromStr for Schedule {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if let Ok(interval) = humantime::parse_duration(value) {
+            if interval.is_zero() {
+                return Err("Sync interval must be longer than zero".to_string());
+            }
+            return Ok(Self::Interval(interval));
+        }
+        // The cron crate wants a seconds field, which the usual five-field syntax lacks
+        let expression = if value.split_whitespace().count() == 5 {
+            format!("0 {value}")
+        } else {
+            value.to_string()
+        };
+        cron::Schedule::from_str(&expression)
+            .map(|schedule| Self::Cron(Box::new(schedule)))
+            .map_err(|e| format!("'{value}' is neither an interval nor a cron expression: {e}"))
+    }
+}
+
+impl fmt::Display for Schedule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Interval(interval) => {
+                write!(f, "every {}", humantime::format_duration(*interval))
+            }
+            Self::Cron(schedule) => write!(f, "cron {}", schedule.source()),
+        }
+    }
+}
+
+impl Schedule {
+    pub fn next_run(&self, last_start: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Self::Interval(interval) => {
+                last_start + serde_with::chrono::Duration::from_std(*interval).unwrap_or_default()
+            }
+            Self::Cron(schedule) => schedule
+                .after(&last_start.with_timezone(&config::get_timezone()))
+                .next()
+                .map(|next| next.with_timezone(&Utc))
+                .unwrap_or(DateTime::<Utc>::MAX_UTC),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn intervals_are_parsed_as_durations() {
+        let schedule = " 1h 30m ".parse::<Schedule>().unwrap();
+        assert!(matches!(schedule, Schedule::Interval(interval) if interval.as_secs() == 5_400));
+        assert_eq!(schedule.to_string(), "every 1h 30m");
+    }
+
+    #[test]
+    fn zero_intervals_are_rejected() {
+        assert!("0s".parse::<Schedule>().is_err());
+    }
+
+    #[test]
+    fn five_field_cron_expressions_get_a_seconds_field() {
+        let schedule = "0 3 * * *".parse::<Schedule>().unwrap();
+        assert_eq!(schedule.to_string(), "cron 0 0 3 * * *");
+    }
+
+    #[test]
+    fn six_field_cron_expressions_are_kept() {
+        let schedule = "30 0 3 * * *".parse::<Schedule>().unwrap();
+        assert_eq!(schedule.to_string(), "cron 30 0 3 * * *");
+    }
+
+    #[test]
+    fn anything_else_is_rejected() {
+        let error = "sometimes".parse::<Schedule>().unwrap_err();
+        assert!(error.starts_with("'sometimes' is neither an interval nor a cron expression"));
+    }
+
+    #[test]
+    fn intervals_run_after_the_last_start() {
+        let last_start = DateTime::from_timestamp(1_000_000, 0).unwrap();
+        let schedule = "6h".parse::<Schedule>().unwrap();
+        assert_eq!(
+            schedule.next_run(last_start).timestamp(),
+            1_000_000 + 6 * 3_600
+        );
+    }
+}
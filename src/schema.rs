@@ -17,11 +17,13 @@ pub struct Account {
     pub iban: Option<String>,
 }
 
+/// A jar, whose statement is fetched like a card's, see `sync::jar_account`.
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 #[allow(dead_code)]
 pub struct Jar {
     pub id: String,
+    pub send_id: String,
     pub title: String,
     pub description: String,
     pub currency_code: u32,
@@ -54,12 +56,40 @@ pub struct StatementItem {
     pub counter_name: Option<String>,
 }
 
+/// One pair from the public `/bank/currency` endpoint, rates are B units per A unit.
+#[serde_with::serde_as]
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CurrencyRate {
+    pub currency_code_a: u32,
+    pub currency_code_b: u32,
+    #[serde_as(as = "TimestampSeconds<i64, Flexible>")]
+    pub date: DateTime<Utc>,
+    pub rate_buy: Option<f64>,
+    pub rate_sell: Option<f64>,
+    pub rate_cross: Option<f64>,
+}
+
 #[derive(Debug, Clone, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ClientInfo {
     pub client_id: String,
     pub name: String,
+    pub web_hook_url: Option<String>,
     pub accounts: Vec<Account>,
-    #[allow(dead_code)]
-    jars: Option<Vec<Jar>>,
+    pub jars: Option<Vec<Jar>>,
+}
+
+/// Body of the POST requests Monobank sends to the registered webhook.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum WebhookEvent {
+    StatementItem(WebhookStatementItem),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebhookStatementItem {
+    pub account: String,
+    pub statement_item: StatementItem,
 }
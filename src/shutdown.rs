@@ -0,0 +1,65 @@
+use tokio::sync::watch;
+
+/// Cooperative stop flag: the sync finishes the window it is on and then stops fetching.
+#[derive(Debug, Clone)]
+pub struct Shutdown {
+    sender: watch::Sender<bool>,
+    receiver: watch::Receiver<bool>,
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        let (sender, receiver) = watch::channel(false);
+        Self { sender, receiver }
+    }
+
+    pub fn request(&self) {
+        self.sender.send_replace(true);
+    }
+
+    pub fn requested(&self) -> bool {
+        *self.receiver.borrow()
+    }
+
+    pub async fn wait(&self) {
+        let mut receiver = self.receiver.clone();
+        // The sender lives in `self`, so the channel can't close while we wait
+        let _ = receiver.wait_for(|requested| *requested).await;
+    }
+
+    /// Requests a shutdown on SIGINT, and on SIGTERM where there is one.
+    /// A second signal of either kind exits right away.
+    pub fn listen_for_signals(&self) {
+        let shutdown = self.clone();
+        tokio::spawn(async move {
+            #[cfg(unix)]
+            let mut terminate = {
+                use tokio::signal::unix::{signal, SignalKind};
+                signal(SignalKind::terminate()).expect("Failed to listen for SIGTERM")
+            };
+            loop {
+                #[cfg(unix)]
+                tokio::select! {
+                    _ = tokio::signal::ctrl_c() => {}
+                    _ = terminate.recv() => {}
+                }
+                #[cfg(not(unix))]
+                tokio::signal::ctrl_c()
+                    .await
+                    .expect("Failed to listen for Ctrl-C");
+                if shutdown.requested() {
+                    tracing::warn!("Interrupted again, exiting without finishing the window");
+                    std::process::exit(130);
+                }
+                tracing::info!("Shutdown requested, finishing the current window...");
+                shutdown.request();
+            }
+        });
+    }
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
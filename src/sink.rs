@@ -0,0 +1,186 @@
+use crate::config;
+use crate::db_types::DatabasePool;
+use crate::models;
+#[cfg(feature = "parquet")]
+use crate::parquet_sink::ParquetSink;
+use serde::Serialize;
+use std::error::Error;
+use std::fs::OpenOptions;
+use std::io::{self, BufWriter, Write};
+use std::sync::Mutex;
+
+pub type SinkError = Box<dyn Error + Send + Sync>;
+
+/// Somewhere synced data goes besides the database.
+///
+/// Sinks get every account as it is synced and every statement item once it is
+/// newly stored, so they never see an item twice. The Parquet dataset is the exception
+/// and exports stored items itself, see `Sinks::export_stored`.
+pub trait Sink: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    fn write_account(&self, account: &models::Account, synced_at: u32) -> Result<(), SinkError>;
+
+    /// `window_end` is the cursor the items moved their account to.
+    fn write_statement_items(
+        &self,
+        account_id: &str,
+        window_end: u32,
+        items: &[models::StatementItem],
+    ) -> Result<(), SinkError>;
+}
+
+/// The configured sinks, which only log what they fail to write so syncing goes on.
+#[derive(Default)]
+pub struct Sinks {
+    sinks: Vec<Box<dyn Sink>>,
+    exclusive: bool,
+    /// Also among `sinks` for accounts, statement items are exported after each run.
+    #[cfg(feature = "parquet")]
+    parquet: Option<ParquetSink>,
+}
+
+impl Sinks {
+    /// Sinks for `NDJSON_OUTPUT` and, with the `parquet` feature, `PARQUET_OUTPUT_DIR`,
+    /// used instead of storing statement items with `NDJSON_ONLY`.
+    pub fn from_config() -> Result<Self, String> {
+        let exclusive = config::get_ndjson_only();
+        if exclusive && config::get_ndjson_output().is_none() {
+            return Err("NDJSON_ONLY needs NDJSON_OUTPUT to be set".to_string());
+        }
+        let mut sinks: Vec<Box<dyn Sink>> = Vec::new();
+        #[cfg(feature = "parquet")]
+        let parquet = config::get_parquet_output_dir().map(ParquetSink::new);
+        #[cfg(feature = "parquet")]
+        if let Some(parquet) = &parquet {
+            if exclusive {
+                return Err(
+                    "NDJSON_ONLY stores no statement items for PARQUET_OUTPUT_DIR to export"
+                        .to_string(),
+                );
+            }
+            sinks.push(Box::new(parquet.clone()));
+        }
+        if let Some(target) = config::get_ndjson_output() {
+            let sink = NdjsonSink::open(&target)
+                .map_err(|e| format!("Failed to open NDJSON_OUTPUT {target}: {e}"))?;
+            sinks.push(Box::new(sink));
+        }
+        Ok(Self {
+            sinks,
+            exclusive,
+            #[cfg(feature = "parquet")]
+            parquet,
+        })
+    }
+
+    /// Writes the stored statement items `PARQUET_OUTPUT_DIR` is missing, including the
+    /// ones a failed export left out, see `ParquetSink::export_statement_items`.
+    #[cfg_attr(not(feature = "parquet"), allow(unused_variables))]
+    pub async fn export_stored(&self, pool: &DatabasePool) {
+        #[cfg(feature = "parquet")]
+        if let Some(parquet) = &self.parquet {
+            match parquet.export_statement_items(pool).await {
+                Ok(0) => {}
+                Ok(items) => tracing::info!(items, "Exported statement items to Parquet"),
+                Err(e) => tracing::error!("Failed to export statement items to Parquet: {:?}", e),
+            }
+        }
+    }
+
+    /// Whether statement items go only here, neither stored nor moving account cursors,
+    /// so every sink gets all fetched items rather than only newly stored ones.
+    pub fn exclusive(&self) -> bool {
+        self.exclusive
+    }
+
+    pub fn write_account(&self, account: &models::Account, synced_at: u32) {
+        for sink in &self.sinks {
+            if let Err(e) = sink.write_account(account, synced_at) {
+                tracing::warn!(
+                    "Failed to write account {} to {}: {:?}",
+                    account.id,
+                    sink.name(),
+                    e
+                );
+            }
+        }
+    }
+
+    pub fn write_statement_items(
+        &self,
+        account_id: &str,
+        window_end: u32,
+        items: &[models::StatementItem],
+    ) {
+        if items.is_empty() {
+            return;
+        }
+        for sink in &self.sinks {
+            if let Err(e) = sink.write_statement_items(account_id, window_end, items) {
+                tracing::warn!("Failed to write statements to {}: {:?}", sink.name(), e);
+            }
+        }
+    }
+}
+
+/// One line of NDJSON output, told apart by its `type`.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum Record<'a> {
+    Account(&'a models::Account),
+    StatementItem(&'a models::StatementItem),
+}
+
+/// Writes a JSON object per line to stdout or appends them to a file, for `jq` and friends.
+pub struct NdjsonSink {
+    out: Mutex<Box<dyn Write + Send>>,
+}
+
+impl NdjsonSink {
+    /// `-` is stdout, anything else a file that is appended to.
+    pub fn open(target: &str) -> io::Result<Self> {
+        let out: Box<dyn Write + Send> = if target == "-" {
+            Box::new(io::stdout())
+        } else {
+            let file = OpenOptions::new().create(true).append(true).open(target)?;
+            Box::new(BufWriter::new(file))
+        };
+        Ok(Self {
+            out: Mutex::new(out),
+        })
+    }
+
+    fn write_records<'a>(
+        &self,
+        records: impl IntoIterator<Item = Record<'a>>,
+    ) -> Result<(), SinkError> {
+        let mut out = self.out.lock().expect("NDJSON output lock poisoned");
+        for record in records {
+            serde_json::to_writer(&mut *out, &record)?;
+            out.write_all(b"\n")?;
+        }
+        // Flushed per batch, so readers at the other end of a pipe see items as they come
+        out.flush()?;
+        Ok(())
+    }
+}
+
+impl Sink for NdjsonSink {
+    fn name(&self) -> &'static str {
+        "NDJSON"
+    }
+
+    fn write_account(&self, account: &models::Account, _synced_at: u32) -> Result<(), SinkError> {
+        self.write_records([Record::Account(account)])
+    }
+
+    fn write_statement_items(
+        &self,
+        _account_id: &str,
+        _window_end: u32,
+        items: &[models::StatementItem],
+    ) -> Result<(), SinkError> {
+        self.write_records(items.iter().map(Record::StatementItem))
+    }
+}
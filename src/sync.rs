@@ -0,0 +1,470 @@
+use crate::categories::Categorizer;
+use crate::db_types::DatabasePool;
+use crate::shutdown::Shutdown;
+use crate::sink::Sinks;
+use crate::{api, config, conversion, crud, models, rates, schema, transfers, utils};
+use reqwest::Client;
+use serde_with::chrono::NaiveDateTime;
+use std::sync::{Arc, RwLock};
+
+pub struct Syncer {
+    pub pool: DatabasePool,
+    pub client: Client,
+    pub rate_limiter: api::RateLimiter,
+    pub shutdown: Shutdown,
+    /// Shared with the webhook server and swapped on SIGHUP by the daemon.
+    pub categorizer: Arc<RwLock<Categorizer>>,
+    /// Shared with the webhook server, which hands pushed items to the same outputs.
+    pub sinks: Arc<Sinks>,
+}
+
+/// A `sync_runs` row being filled in, errors are joined when the run is finished.
+pub struct RunLog {
+    pub run: models::SyncRun,
+    pub errors: Vec<String>,
+}
+
+/// Statement windows to fetch for one account, from right after `cursor` up to `end_time`.
+#[derive(Debug, Clone)]
+pub struct AccountPlan {
+    pub token: String,
+    pub account_id: String,
+    pub cursor: u32,
+    pub end_time: u32,
+}
+
+impl AccountPlan {
+    pub fn windows(&self) -> usize {
+        api::plan_windows(self.cursor, self.end_time).len()
+    }
+}
+
+/// Counts fetched windows against the plan and logs how much waiting is left.
+#[derive(Debug)]
+pub struct Progress {
+    done: usize,
+    total: usize,
+}
+
+impl Progress {
+    pub fn new(plans: &[AccountPlan]) -> Self {
+        let total = plans.iter().map(AccountPlan::windows).sum();
+        tracing::info!(
+            accounts = plans.len(),
+            requests = total,
+            eta_minutes = eta_minutes(total),
+            "Sync plan",
+        );
+        Self { done: 0, total }
+    }
+
+    fn advance(&mut self) {
+        self.done += 1;
+        // 500-item splits add requests the plan did not know about
+        self.total = self.total.max(self.done);
+        tracing::info!(
+            window = self.done,
+            of = self.total,
+            eta_minutes = eta_minutes(self.total - self.done),
+            "Sync progress",
+        );
+    }
+
+    fn skip(&mut self, windows: usize) {
+        self.total = self.total.saturating_sub(windows).max(self.done);
+    }
+}
+
+pub fn account_from(
+    client_id: &str,
+    raw: schema::Account,
+    last_sync_at: Option<NaiveDateTime>,
+) -> models::Account {
+    models::Account {
+        id: raw.id,
+        client_id: client_id.to_string(),
+        send_id: raw.send_id,
+        iban: raw.iban,
+        account_type: raw.account_type,
+        currency_code: raw.currency_code,
+        balance: raw.balance,
+        credit_limit: raw.credit_limit,
+        cashback_type: raw.cashback_type,
+        last_sync_at,
+    }
+}
+
+pub fn statement_item_from(account_id: &str, s: schema::StatementItem) -> models::StatementItem {
+    models::StatementItem {
+        id: s.id,
+        account_id: account_id.to_string(),
+        time: utils::datetime_from_utc_to_tz(s.time),
+        description: s.description,
+        mcc: s.mcc,
+        original_mcc: s.original_mcc,
+        hold: s.hold,
+        amount: s.amount,
+        operation_amount: s.operation_amount,
+        currency_code: s.currency_code,
+        commission_rate: s.commission_rate,
+        cashback_amount: s.cashback_amount,
+        balance: s.balance,
+        comment: s.comment,
+        receipt_id: s.receipt_id,
+        invoice_id: s.invoice_id,
+        counter_iban: s.counter_iban,
+        counter_name: s.counter_name,
+        counter_edrpou: s.counter_edrpou,
+        category: None,
+    }
+}
+
+pub fn log_summary(runs: &[models::SyncRun], interrupted: bool) {
+    let total = |field: fn(&models::SyncRun) -> i64| runs.iter().map(field).sum::<i64>();
+    tracing::info!(
+        tokens = runs.len(),
+        accounts = total(|r| r.accounts_touched),
+        windows = total(|r| r.windows_fetched),
+        items_fetched = total(|r| r.items_fetched),
+        items_inserted = total(|r| r.items_inserted),
+        failed_runs = runs.iter().filter(|r| r.errors.is_some()).count(),
+        interrupted,
+        "Sync finished",
+    );
+    if interrupted {
+        tracing::info!("Stopped early, the next sync resumes from the stored cursors");
+    }
+}
+
+pub fn eta_minutes(requests: usize) -> u32 {
+    api::estimate_wait_sec(requests as u32).div_ceil(60)
+}
+
+/// A jar as an account of type `jar`, so its statement is synced like a card's and
+/// transfers between cards and jars get both of their sides.
+pub fn jar_account(jar: schema::Jar) -> schema::Account {
+    schema::Account {
+        id: jar.id,
+        send_id: jar.send_id,
+        balance: jar.balance,
+        credit_limit: 0,
+        account_type: "jar".to_string(),
+        currency_code: jar.currency_code,
+        cashback_type: None,
+        iban: None,
+    }
+}
+
+/// Accounts whose type is in `ALLOWED_CARD_TYPES`, jars too when it lists `jar`.
+pub fn relevant_accounts(client_info: &schema::ClientInfo) -> Vec<schema::Account> {
+    let allowed_card_types = config::get_all_allowed_card_types();
+    let jars = client_info.jars.iter().flatten().cloned().map(jar_account);
+    client_info
+        .accounts
+        .iter()
+        .cloned()
+        .chain(jars)
+        .filter(|ac| allowed_card_types.contains(&ac.account_type))
+        .collect()
+}
+
+impl Syncer {
+    /// `categorizer` and `sinks` are left empty by commands that store no new statement items.
+    pub fn new(pool: DatabasePool, client: Client, categorizer: Categorizer, sinks: Sinks) -> Self {
+        let shutdown = Shutdown::new();
+        Self {
+            pool,
+            client,
+            rate_limiter: api::RateLimiter::new(
+                api::WAIT_TIME_SEC,
+                api::WAIT_JITTER_SEC,
+                shutdown.clone(),
+            ),
+            shutdown,
+            categorizer: Arc::new(RwLock::new(categorizer)),
+            sinks: Arc::new(sinks),
+        }
+    }
+
+    /// Re-reads `CATEGORIES_FILE`, keeping the current categories if it is broken.
+    pub fn reload_categorizer(&self) {
+        match Categorizer::load() {
+            Ok(categorizer) => {
+                *self.categorizer.write().expect("Categorizer lock poisoned") = categorizer
+            }
+            Err(e) => tracing::warn!("Keeping the previous categories: {}", e),
+        }
+    }
+
+    pub async fn sync_all(&self, tokens: &[String], time_floor: NaiveDateTime) {
+        if config::get_sync_currency_rates() {
+            rates::sync(&self.pool, &self.client).await;
+        }
+        let now = utils::current_timestamp();
+        let mut runs = Vec::new();
+        let mut finished = Vec::new();
+        for token in tokens {
+            let mut log = self.start_run(token).await;
+            match self.prepare_token(&mut log, token, time_floor, now).await {
+                Some(plans) => runs.push((log, plans)),
+                None => finished.push(self.finish_run(log).await),
+            }
+        }
+        let all_plans = runs
+            .iter()
+            .flat_map(|(_, plans)| plans.iter().cloned())
+            .collect::<Vec<AccountPlan>>();
+        let earliest = all_plans.iter().map(|plan| plan.cursor + 1).min();
+        let mut progress = Progress::new(&all_plans);
+        for (mut log, plans) in runs {
+            for plan in &plans {
+                if self.shutdown.requested() {
+                    break;
+                }
+                self.fetch_windows(&mut log, plan, &mut progress, true)
+                    .await;
+            }
+            finished.push(self.finish_run(log).await);
+        }
+        if !self.shutdown.requested() {
+            conversion::update_base_amounts(&self.pool, config::get_base_currency(), false).await;
+            if let Some(earliest) = earliest {
+                transfers::detect(&self.pool, Some(utils::datetime_from(earliest))).await;
+            }
+            self.sinks.export_stored(&self.pool).await;
+        }
+        log_summary(&finished, self.shutdown.requested());
+    }
+
+    /// Stores client info and accounts of a token and plans what to fetch for them.
+    async fn prepare_token(
+        &self,
+        log: &mut RunLog,
+        token: &str,
+        time_floor: NaiveDateTime,
+        now: u32,
+    ) -> Option<Vec<AccountPlan>> {
+        let raw_client_info = match api::fetch_client_info(&self.client, token).await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::error!("Failed to fetch client info: {:?}", e);
+                log.errors.push(format!("client info: {e}"));
+                return None;
+            }
+        };
+        let accounts = relevant_accounts(&raw_client_info);
+        let client_info = models::ClientInfo {
+            client_id: raw_client_info.client_id,
+            name: raw_client_info.name,
+            token: token.to_string(),
+        };
+        if let Err(e) = crud::insert_client_info(&self.pool, client_info.clone()).await {
+            tracing::warn!("Failed to insert client info: {:?}", e);
+        }
+        let webhook_url = raw_client_info.web_hook_url.filter(|url| !url.is_empty());
+        if let Err(e) = crud::update_webhook_url(&self.pool, token, webhook_url.as_deref()).await {
+            tracing::warn!("Failed to update client webhook: {:?}", e);
+        }
+        let mut plans = Vec::new();
+        for raw_account in accounts {
+            log.run.accounts_touched += 1;
+            let account_id = raw_account.id.clone();
+            let last_sync_time = crud::get_last_sync_time(&self.pool, account_id.clone())
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or(time_floor);
+            let account = account_from(&client_info.client_id, raw_account, Some(last_sync_time));
+            if let Err(e) = crud::insert_account(&self.pool, account.clone()).await {
+                tracing::warn!("Failed to insert account {}: {:?}", account.id, e);
+            }
+            if let Err(e) = crud::update_account_balance(
+                &self.pool,
+                &account.id,
+                account.balance,
+                account.credit_limit,
+            )
+            .await
+            {
+                tracing::warn!("Failed to update balance of {}: {:?}", account.id, e);
+            }
+            tracing::info!(account_id = account.id, balance = %account.balance_money(), "Account");
+            self.sinks.write_account(&account, now);
+            plans.push(AccountPlan {
+                token: token.to_string(),
+                account_id: account.id,
+                cursor: utils::timestamp_from(last_sync_time) - 1,
+                end_time: now,
+            });
+        }
+        Some(plans)
+    }
+
+    pub async fn start_run(&self, token: &str) -> RunLog {
+        let mut run = models::SyncRun {
+            token_fingerprint: utils::token_fingerprint(token),
+            started_at: utils::datetime_from(utils::current_timestamp()),
+            ..Default::default()
+        };
+        match crud::start_sync_run(&self.pool, &run.token_fingerprint, run.started_at).await {
+            Ok(id) => run.id = id,
+            Err(e) => tracing::warn!("Failed to record sync run: {:?}", e),
+        }
+        RunLog {
+            run,
+            errors: Vec::new(),
+        }
+    }
+
+    pub async fn finish_run(&self, log: RunLog) -> models::SyncRun {
+        let mut run = log.run;
+        run.finished_at = Some(utils::datetime_from(utils::current_timestamp()));
+        if !log.errors.is_empty() {
+            run.errors = Some(log.errors.join("\n"));
+        }
+        if let Err(e) = crud::finish_sync_run(&self.pool, &run).await {
+            tracing::warn!("Failed to record sync run result: {:?}", e);
+        }
+        run
+    }
+
+    /// Fetches and stores the planned windows one at a time.
+    /// The account's `last_sync_at` follows the fetched windows only when `move_cursor` is set,
+    /// and never when the sinks replace the database.
+    pub async fn fetch_windows(
+        &self,
+        log: &mut RunLog,
+        plan: &AccountPlan,
+        progress: &mut Progress,
+        move_cursor: bool,
+    ) {
+        let account_id = plan.account_id.as_str();
+        let exclusive = self.sinks.exclusive();
+        // Cursors only follow stored items, so with NDJSON_ONLY the next run starts over
+        let move_cursor = move_cursor && !exclusive;
+        let mut window_start = plan.cursor + 1;
+        let mut card_statements = api::FetchingStatements {
+            client: &self.client,
+            rate_limiter: &self.rate_limiter,
+            token: plan.token.clone(),
+            account_id: plan.account_id.clone(),
+            last_success_time: plan.cursor,
+            end_time: plan.end_time,
+        };
+        while let Some(statement_response) = card_statements.fetch_next().await {
+            let (window_end, raw_statements) = match statement_response {
+                Ok(window) => window,
+                Err((timestamp, e)) => {
+                    tracing::error!("Error fetching statements: {:?}", e);
+                    progress.skip(api::plan_windows(timestamp, plan.end_time).len());
+                    if move_cursor {
+                        let last_success = utils::datetime_from(timestamp);
+                        if let Err(e) = crud::update_last_sync_time(
+                            &self.pool,
+                            account_id.to_string(),
+                            Some(last_success),
+                        )
+                        .await
+                        {
+                            tracing::warn!("Failed to update sync time after error: {:?}", e);
+                        }
+                    }
+                    log.errors.push(format!("account {account_id}: {e}"));
+                    self.record_window(models::SyncWindow {
+                        run_id: log.run.id,
+                        account_id: account_id.to_string(),
+                        window_start: utils::datetime_from(window_start),
+                        window_end: None,
+                        status: "failed".to_string(),
+                        items_fetched: 0,
+                        items_inserted: 0,
+                        error: Some(e.to_string()),
+                        fetched_at: utils::datetime_from(utils::current_timestamp()),
+                    })
+                    .await;
+                    break;
+                }
+            };
+            let items_fetched = raw_statements.len() as i64;
+            let (statements, tags): (Vec<models::StatementItem>, Vec<Vec<String>>) = {
+                let categorizer = self.categorizer.read().expect("Categorizer lock poisoned");
+                raw_statements
+                    .into_iter()
+                    .map(|s| {
+                        let mut item = statement_item_from(account_id, s);
+                        let tags = categorizer.categorize(&mut item);
+                        (item, tags)
+                    })
+                    .unzip()
+            };
+            let mut inserted = Vec::new();
+            if exclusive {
+                self.sinks
+                    .write_statement_items(account_id, window_end, &statements);
+            } else {
+                for (statement_item, tags) in statements.into_iter().zip(tags) {
+                    match crud::insert_statement_item(&self.pool, statement_item.clone()).await {
+                        Ok(true) => {
+                            self.store_rule_tags(&statement_item.id, &tags).await;
+                            inserted.push(statement_item);
+                        }
+                        Ok(false) => {}
+                        Err(e) => tracing::warn!("Failed to insert statement: {:?}", e),
+                    }
+                }
+                self.sinks
+                    .write_statement_items(account_id, window_end, &inserted);
+            }
+            let items_inserted = inserted.len() as i64;
+            // Only after the items are stored, so an interrupted window is fetched again
+            let last_success = utils::datetime_from(window_end);
+            if move_cursor {
+                if let Err(e) = crud::update_last_sync_time(
+                    &self.pool,
+                    account_id.to_string(),
+                    Some(last_success),
+                )
+                .await
+                {
+                    tracing::warn!("Failed to update sync time: {:?}", e);
+                }
+            }
+            log.run.windows_fetched += 1;
+            log.run.items_fetched += items_fetched;
+            log.run.items_inserted += items_inserted;
+            self.record_window(models::SyncWindow {
+                run_id: log.run.id,
+                account_id: account_id.to_string(),
+                window_start: utils::datetime_from(window_start),
+                window_end: Some(last_success),
+                status: "success".to_string(),
+                items_fetched,
+                items_inserted,
+                error: None,
+                fetched_at: utils::datetime_from(utils::current_timestamp()),
+            })
+            .await;
+            progress.advance();
+            window_start = window_end + 1;
+            if self.shutdown.requested() {
+                tracing::info!(account_id, "Stopping after the current window");
+                break;
+            }
+        }
+    }
+
+    async fn store_rule_tags(&self, item_id: &str, tags: &[String]) {
+        if tags.is_empty() {
+            return;
+        }
+        if let Err(e) = crud::replace_rule_tags(&self.pool, item_id, tags).await {
+            tracing::warn!("Failed to store tags of {}: {:?}", item_id, e);
+        }
+    }
+
+    async fn record_window(&self, window: models::SyncWindow) {
+        if let Err(e) = crud::insert_sync_window(&self.pool, window).await {
+            tracing::warn!("Failed to record sync window: {:?}", e);
+        }
+    }
+}
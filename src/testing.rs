@@ -0,0 +1,44 @@
+//! Builders the unit tests and the integration tests in `tests/` share, behind the
+//! `test-util` feature outside of `cargo test`.
+
+use crate::models;
+use serde_with::chrono::{NaiveDate, NaiveDateTime};
+
+/// `hour` o'clock on a day of October 2026.
+pub fn at(day: u32, hour: u32) -> NaiveDateTime {
+    NaiveDate::from_ymd_opt(2026, 10, day)
+        .unwrap()
+        .and_hms_opt(hour, 0, 0)
+        .unwrap()
+}
+
+/// A settled hryvnia payment at a coffee shop, for tests to change what they look at.
+pub fn statement_item(
+    id: &str,
+    account_id: &str,
+    time: NaiveDateTime,
+    amount: i64,
+) -> models::StatementItem {
+    models::StatementItem {
+        id: id.to_string(),
+        account_id: account_id.to_string(),
+        time,
+        description: "Coffee Point".to_string(),
+        mcc: 5814,
+        original_mcc: 5814,
+        hold: false,
+        amount,
+        operation_amount: amount,
+        currency_code: 980,
+        commission_rate: 0,
+        cashback_amount: 0,
+        balance: 0,
+        comment: None,
+        receipt_id: None,
+        invoice_id: None,
+        counter_edrpou: None,
+        counter_iban: None,
+        counter_name: None,
+        category: Some("cafes".to_string()),
+    }
+}
@@ -0,0 +1,269 @@
+use crate::db_types::DatabasePool;
+use crate::{config, crud, models, utils};
+use regex::Regex;
+use serde_with::chrono::{Duration, NaiveDateTime};
+use std::collections::{HashMap, HashSet};
+
+/// Pairs outflows with inflows on another account of the same client and records them
+/// in `internal_transfers`, so reports can leave money moved between own cards out.
+///
+/// Both sides must cancel out, either in the accounts' common currency or in the
+/// operation currency, and be booked within `TRANSFER_MATCH_WINDOW` of each other.
+/// On top of that one side must name an own account's IBAN as its counterparty or
+/// both must look like a transfer per `TRANSFER_DESCRIPTION_PATTERN`. Among several
+/// candidates the inflow closest in time wins.
+pub async fn detect(pool: &DatabasePool, since: Option<NaiveDateTime>) {
+    let window = Duration::from_std(config::get_transfer_match_window())
+        .expect("TRANSFER_MATCH_WINDOW is out of range");
+    let pattern = match Regex::new(&config::get_transfer_description_pattern()) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            tracing::error!("Invalid TRANSFER_DESCRIPTION_PATTERN: {}", e);
+            return;
+        }
+    };
+    let mut own_ibans: HashMap<String, HashSet<String>> = HashMap::new();
+    match crud::get_account_ibans(pool).await {
+        Ok(ibans) => {
+            for account in ibans {
+                own_ibans
+                    .entry(account.client_id)
+                    .or_default()
+                    .insert(account.iban);
+            }
+        }
+        Err(e) => {
+            tracing::error!("Failed to get account IBANs: {:?}", e);
+            return;
+        }
+    }
+    // The other side of the first transfers may be booked before `since`
+    let since = since.map(|since| since - window);
+    let candidates = match crud::get_transfer_candidates(pool, since).await {
+        Ok(candidates) => candidates,
+        Err(e) => {
+            tracing::error!("Failed to get transfer candidates: {:?}", e);
+            return;
+        }
+    };
+    let pairs = find_pairs(&candidates, &own_ibans, &pattern, window);
+    let matched_at = utils::datetime_from(utils::current_timestamp());
+    let mut matched = 0;
+    for (outflow, inflow, matched_by) in pairs {
+        match crud::insert_internal_transfer(pool, &outflow.id, &inflow.id, matched_by, matched_at)
+            .await
+        {
+            Ok(true) => matched += 1,
+            Ok(false) => {}
+            Err(e) => tracing::warn!(
+                "Failed to record transfer {} -> {}: {:?}",
+                outflow.id,
+                inflow.id,
+                e
+            ),
+        }
+    }
+    if matched > 0 {
+        tracing::info!(matched, "Matched internal transfers");
+    }
+}
+
+fn find_pairs<'a>(
+    candidates: &'a [models::TransferCandidate],
+    own_ibans: &HashMap<String, HashSet<String>>,
+    pattern: &Regex,
+    window: Duration,
+) -> Vec<(
+    &'a models::TransferCandidate,
+    &'a models::TransferCandidate,
+    &'static str,
+)> {
+    let is_own_iban = |item: &models::TransferCandidate| {
+        item.counter_iban.as_ref().is_some_and(|iban| {
+            own_ibans
+                .get(&item.client_id)
+                .is_some_and(|ibans| ibans.contains(iban))
+        })
+    };
+    let inflows = candidates
+        .iter()
+        .filter(|item| item.amount > 0)
+        .collect::<Vec<&models::TransferCandidate>>();
+    let mut used = HashSet::new();
+    let mut pairs = Vec::new();
+    for outflow in candidates.iter().filter(|item| item.amount < 0) {
+        let best = inflows
+            .iter()
+            .filter(|inflow| {
+                inflow.client_id == outflow.client_id
+                    && inflow.account_id != outflow.account_id
+                    && !used.contains(&inflow.id)
+                    && (inflow.time - outflow.time).abs() <= window
+                    && amounts_cancel_out(outflow, inflow)
+            })
+            .filter_map(|inflow| {
+                let matched_by = if is_own_iban(outflow) || is_own_iban(inflow) {
+                    "counter_iban"
+                } else if pattern.is_match(&outflow.description)
+                    && pattern.is_match(&inflow.description)
+                {
+                    "description"
+                } else {
+                    return None;
+                };
+                Some((*inflow, matched_by))
+            })
+            .min_by_key(|(inflow, _)| (inflow.time - outflow.time).abs());
+        if let Some((inflow, matched_by)) = best {
+            used.insert(&inflow.id);
+            pairs.push((outflow, inflow, matched_by));
+        }
+    }
+    pairs
+}
+
+fn amounts_cancel_out(
+    outflow: &models::TransferCandidate,
+    inflow: &models::TransferCandidate,
+) -> bool {
+    let same_account_currency =
+        outflow.account_currency == inflow.account_currency && outflow.amount == -inflow.amount;
+    let same_operation = outflow.currency_code == inflow.currency_code
+        && outflow.operation_amount == -inflow.operation_amount;
+    same_account_currency || same_operation
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_with::chrono::NaiveDate;
+
+    fn candidate(
+        id: &str,
+        account_id: &str,
+        minute: u32,
+        amount: i64,
+    ) -> models::TransferCandidate {
+        models::TransferCandidate {
+            id: id.to_string(),
+            account_id: account_id.to_string(),
+            client_id: "client".to_string(),
+            time: NaiveDate::from_ymd_opt(2026, 10, 1)
+                .unwrap()
+                .and_hms_opt(12, minute, 0)
+                .unwrap(),
+            description: "Coffee".to_string(),
+            amount,
+            operation_amount: amount,
+            currency_code: 980,
+            account_currency: 980,
+            counter_iban: None,
+        }
+    }
+
+    fn pairs_of(
+        candidates: &[models::TransferCandidate],
+        own_ibans: &HashMap<String, HashSet<String>>,
+    ) -> Vec<(String, String, &'static str)> {
+        let pattern = Regex::new("(?i)transfer").unwrap();
+        find_pairs(candidates, own_ibans, &pattern, Duration::minutes(10))
+            .into_iter()
+            .map(|(outflow, inflow, matched_by)| {
+                (outflow.id.clone(), inflow.id.clone(), matched_by)
+            })
+            .collect()
+    }
+
+    fn pair(
+        outflow: &str,
+        inflow: &str,
+        matched_by: &'static str,
+    ) -> (String, String, &'static str) {
+        (outflow.to_string(), inflow.to_string(), matched_by)
+    }
+
+    #[test]
+    fn own_counter_ibans_match() {
+        let mut outflow = candidate("out", "black", 0, -1_000);
+        outflow.counter_iban = Some("UA-white".to_string());
+        let candidates = [outflow, candidate("in", "white", 1, 1_000)];
+        let own_ibans = HashMap::from([(
+            "client".to_string(),
+            HashSet::from(["UA-white".to_string()]),
+        )]);
+        assert_eq!(
+            pairs_of(&candidates, &own_ibans),
+            [pair("out", "in", "counter_iban")]
+        );
+    }
+
+    #[test]
+    fn descriptions_match_only_on_both_sides() {
+        let mut outflow = candidate("out", "black", 0, -1_000);
+        outflow.description = "Transfer to white".to_string();
+        let candidates = [outflow.clone(), candidate("in", "white", 1, 1_000)];
+        assert!(pairs_of(&candidates, &HashMap::new()).is_empty());
+
+        let mut inflow = candidate("in", "white", 1, 1_000);
+        inflow.description = "Transfer from black".to_string();
+        assert_eq!(
+            pairs_of(&[outflow, inflow], &HashMap::new()),
+            [pair("out", "in", "description")]
+        );
+    }
+
+    #[test]
+    fn the_closest_inflow_wins_and_is_used_once() {
+        let mut candidates = vec![
+            candidate("out-1", "black", 5, -1_000),
+            candidate("out-2", "black", 6, -1_000),
+            candidate("far", "white", 12, 1_000),
+            candidate("near", "white", 4, 1_000),
+            candidate("late", "white", 30, 1_000),
+        ];
+        for item in &mut candidates {
+            item.description = "Transfer".to_string();
+        }
+        assert_eq!(
+            pairs_of(&candidates, &HashMap::new()),
+            [
+                pair("out-1", "near", "description"),
+                pair("out-2", "far", "description")
+            ]
+        );
+    }
+
+    #[test]
+    fn amounts_cancel_out_in_either_currency() {
+        let mut outflow = candidate("out", "black", 0, -4_150);
+        outflow.operation_amount = -100;
+        outflow.currency_code = 840;
+        outflow.description = "Transfer".to_string();
+        let mut inflow = candidate("in", "usd", 1, 100);
+        inflow.currency_code = 840;
+        inflow.account_currency = 840;
+        inflow.description = "Transfer".to_string();
+        assert_eq!(
+            pairs_of(&[outflow.clone(), inflow.clone()], &HashMap::new()),
+            [pair("out", "in", "description")]
+        );
+
+        inflow.operation_amount = 99;
+        inflow.amount = 99;
+        assert!(pairs_of(&[outflow, inflow], &HashMap::new()).is_empty());
+    }
+
+    #[test]
+    fn same_account_and_other_clients_never_match() {
+        let mut candidates = vec![
+            candidate("out", "black", 0, -1_000),
+            candidate("same", "black", 1, 1_000),
+            candidate("other", "white", 1, 1_000),
+        ];
+        candidates[2].client_id = "someone".to_string();
+        for item in &mut candidates {
+            item.description = "Transfer".to_string();
+        }
+        assert!(pairs_of(&candidates, &HashMap::new()).is_empty());
+    }
+}
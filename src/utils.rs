@@ -1,6 +1,8 @@
 use crate::config;
 use chrono_tz::Tz;
-use serde_with::chrono::{DateTime, NaiveDateTime, Utc};
+use serde_with::chrono::{DateTime, NaiveDate, NaiveDateTime, TimeZone, Utc};
+use sha2::{Digest, Sha256};
+use std::time::{SystemTime, UNIX_EPOCH};
 
 pub fn datetime_from(timestamp: u32) -> NaiveDateTime {
     let tz: Tz = config::get_timezone();
@@ -15,3 +17,29 @@ pub fn datetime_from_utc_to_tz(utc_dt: DateTime<Utc>) -> NaiveDateTime {
     let tz: Tz = config::get_timezone();
     utc_dt.with_timezone(&tz).naive_local()
 }
+
+/// Inverse of `datetime_from`, reading the naive datetime in the configured timezone.
+pub fn timestamp_from(datetime: NaiveDateTime) -> u32 {
+    let tz: Tz = config::get_timezone();
+    tz.from_local_datetime(&datetime)
+        .earliest()
+        .map(|dt| dt.timestamp())
+        .unwrap_or_else(|| datetime.and_utc().timestamp()) as u32
+}
+
+pub fn day_start_timestamp(day: NaiveDate) -> u32 {
+    timestamp_from(day.and_time(Default::default()))
+}
+
+pub fn current_timestamp() -> u32 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("System time is before UNIX epoch")
+        .as_secs() as u32
+}
+
+/// Short stable identifier for a token, safe to store and log instead of the token itself.
+pub fn token_fingerprint(token: &str) -> String {
+    let digest = Sha256::digest(token.as_bytes());
+    digest[..8].iter().map(|b| format!("{b:02x}")).collect()
+}
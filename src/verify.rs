@@ -0,0 +1,229 @@
+use crate::db_types::DatabasePool;
+use crate::money::Money;
+use crate::{crud, models};
+use std::fmt;
+
+/// Why an item's balance does not follow from the one before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakKind {
+    /// Same time, amount and balance as the previous item under another id.
+    Duplicate,
+    /// The previous item was stored on hold and settled for a different amount.
+    HoldChanged,
+    /// Anything else, usually transactions that were never stored.
+    Missing,
+}
+
+impl fmt::Display for BreakKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BreakKind::Duplicate => write!(f, "duplicate"),
+            BreakKind::HoldChanged => write!(f, "hold amount changed"),
+            BreakKind::Missing => write!(f, "missing transactions"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Break {
+    pub item_id: String,
+    pub previous_item_id: String,
+    pub kind: BreakKind,
+    /// `balance - amount` of the item minus the previous item's `balance`.
+    pub difference: i64,
+}
+
+/// Walks every account's items in time order checking `previous.balance + amount == balance`,
+/// then compares the last balance with `accounts.balance`. Returns whether all of it held.
+pub async fn run(pool: &DatabasePool, account_id: Option<&str>) -> bool {
+    let accounts = match crud::get_account_balances(pool).await {
+        Ok(accounts) => accounts,
+        Err(e) => {
+            tracing::error!("Failed to get accounts: {:?}", e);
+            return false;
+        }
+    };
+    let accounts = accounts
+        .into_iter()
+        .filter(|account| account_id.is_none_or(|id| account.id == id))
+        .collect::<Vec<models::AccountBalance>>();
+    if accounts.is_empty() {
+        tracing::error!("No such account, run a regular sync first");
+        return false;
+    }
+    let mut consistent = true;
+    for account in accounts {
+        let currency = account.currency_code as u32;
+        let items = match crud::get_statement_items(pool, Some(&account.id), None).await {
+            Ok(items) => continuity_order(items),
+            Err(e) => {
+                tracing::error!("Failed to get statement items of {}: {:?}", account.id, e);
+                consistent = false;
+                continue;
+            }
+        };
+        let Some(last) = items.last() else {
+            tracing::info!(account_id = account.id, "No statement items to verify");
+            continue;
+        };
+        let breaks = find_breaks(&items);
+        for b in &breaks {
+            tracing::warn!(
+                account_id = account.id,
+                item_id = b.item_id,
+                previous_item_id = b.previous_item_id,
+                kind = %b.kind,
+                difference = %Money::new(b.difference, currency),
+                "Balance break",
+            );
+        }
+        let balance_matches = last.balance == account.balance;
+        if !balance_matches {
+            tracing::warn!(
+                account_id = account.id,
+                last_item_id = last.id,
+                last_item_balance = %Money::new(last.balance, currency),
+                account_balance = %Money::new(account.balance, currency),
+                "Last statement balance differs from the account balance",
+            );
+        }
+        consistent &= breaks.is_empty() && balance_matches;
+        tracing::info!(
+            account_id = account.id,
+            items = items.len(),
+            breaks = breaks.len(),
+            balance_matches,
+            "Verified account",
+        );
+    }
+    consistent
+}
+
+/// Items sorted by time, with items of the same second chained by their balances
+/// since their stored order says nothing about which came first.
+pub fn continuity_order(mut items: Vec<models::StatementItem>) -> Vec<models::StatementItem> {
+    items.sort_by(|a, b| a.time.cmp(&b.time).then_with(|| a.id.cmp(&b.id)));
+    let mut ordered: Vec<models::StatementItem> = Vec::with_capacity(items.len());
+    let mut rest = items.into_iter().peekable();
+    while let Some(first) = rest.next() {
+        let mut group = vec![first];
+        while let Some(item) = rest.next_if(|item| item.time == group[0].time) {
+            group.push(item);
+        }
+        while !group.is_empty() {
+            let next = match ordered.last() {
+                Some(previous) => group
+                    .iter()
+                    .position(|item| item.balance - item.amount == previous.balance),
+                // The first item is the one no other item of its second leads to
+                None => group.iter().position(|item| {
+                    !group
+                        .iter()
+                        .any(|other| other.balance == item.balance - item.amount)
+                }),
+            };
+            ordered.push(group.remove(next.unwrap_or(0)));
+        }
+    }
+    ordered
+}
+
+pub fn find_breaks(items: &[models::StatementItem]) -> Vec<Break> {
+    items
+        .windows(2)
+        .filter_map(|pair| {
+            let (previous, item) = (&pair[0], &pair[1]);
+            let difference = item.balance - item.amount - previous.balance;
+            if difference == 0 {
+                return None;
+            }
+            let kind = if item.time == previous.time
+                && item.amount == previous.amount
+                && item.balance == previous.balance
+            {
+                BreakKind::Duplicate
+            } else if previous.hold {
+                BreakKind::HoldChanged
+            } else {
+                BreakKind::Missing
+            };
+            Some(Break {
+                item_id: item.id.clone(),
+                previous_item_id: previous.id.clone(),
+                kind,
+                difference,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing;
+    use serde_with::chrono::TimeDelta;
+
+    fn item(id: &str, second: u32, amount: i64, balance: i64) -> models::StatementItem {
+        let time = testing::at(1, 12) + TimeDelta::seconds(second.into());
+        models::StatementItem {
+            balance,
+            ..testing::statement_item(id, "account", time, amount)
+        }
+    }
+
+    fn ids(items: &[models::StatementItem]) -> Vec<&str> {
+        items.iter().map(|item| item.id.as_str()).collect()
+    }
+
+    #[test]
+    fn items_of_the_same_second_are_chained_by_balance() {
+        let items = vec![
+            item("c", 1, -300, 400),
+            item("a", 0, 1_000, 1_000),
+            item("d", 1, -100, 700),
+            item("b", 1, -200, 800),
+        ];
+        let ordered = continuity_order(items);
+        assert_eq!(ids(&ordered), ["a", "b", "d", "c"]);
+        assert!(find_breaks(&ordered).is_empty());
+    }
+
+    #[test]
+    fn first_second_starts_with_the_item_nothing_leads_to() {
+        let items = vec![item("a", 0, -100, 400), item("b", 0, -500, 500)];
+        assert_eq!(ids(&continuity_order(items)), ["b", "a"]);
+    }
+
+    #[test]
+    fn breaks_are_told_apart() {
+        let mut held = item("b", 1, -100, 900);
+        held.hold = true;
+        let items = vec![
+            item("a", 0, -100, 1_000),
+            held,
+            item("c", 2, -50, 800),
+            item("d", 3, -50, 700),
+            item("e", 3, -50, 700),
+        ];
+        let breaks = find_breaks(&items);
+        let found = breaks
+            .iter()
+            .map(|b| {
+                (
+                    b.item_id.as_str(),
+                    b.previous_item_id.as_str(),
+                    b.kind,
+                    b.difference,
+                )
+            })
+            .collect::<Vec<(&str, &str, BreakKind, i64)>>();
+        assert_eq!(
+            found,
+            [
+                ("c", "b", BreakKind::HoldChanged, -50),
+                ("d", "c", BreakKind::Missing, -50),
+                ("e", "d", BreakKind::Duplicate, 50),
+            ]
+        );
+    }
+}
@@ -0,0 +1,224 @@
+use crate::categories::Categorizer;
+use crate::db_types::DatabasePool;
+use crate::schedule::Schedule;
+use crate::sink::Sinks;
+use crate::sync::{self, Syncer};
+use crate::{api, config, crud, daemon, models, schema, utils};
+use axum::extract::State;
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::Router;
+use std::net::SocketAddr;
+use std::sync::{Arc, RwLock};
+
+#[derive(Clone)]
+struct WebhookState {
+    pool: DatabasePool,
+    categorizer: Arc<RwLock<Categorizer>>,
+    sinks: Arc<Sinks>,
+}
+
+/// Stores statement items as Monobank pushes them to `url`, while the regular
+/// scheduled sync keeps running to pick up whatever the webhook missed.
+///
+/// Only the path of `url` is served, so a hard to guess path keeps strangers
+/// from posting made up transactions. Pushed items go to the configured outputs
+/// as soon as they are stored, so the next polled window skips them there too.
+/// The scheduled sync runs on a blocking thread of its own, so a long window
+/// never holds up the acknowledgement Monobank waits for.
+pub async fn serve(syncer: Arc<Syncer>, url: &str, listen: SocketAddr, schedule: Option<Schedule>) {
+    let path = match reqwest::Url::parse(url) {
+        Ok(parsed) => parsed.path().to_string(),
+        Err(e) => {
+            tracing::error!("Invalid webhook URL '{}': {:?}", url, e);
+            return;
+        }
+    };
+    let app = Router::new()
+        .route(&path, get(validate).post(receive))
+        .with_state(WebhookState {
+            pool: syncer.pool.clone(),
+            categorizer: syncer.categorizer.clone(),
+            sinks: syncer.sinks.clone(),
+        });
+    let listener = match tokio::net::TcpListener::bind(listen).await {
+        Ok(listener) => listener,
+        Err(e) => {
+            tracing::error!("Failed to listen on {}: {:?}", listen, e);
+            return;
+        }
+    };
+    let shutdown = syncer.shutdown.clone();
+    let server = tokio::spawn(async move {
+        axum::serve(listener, app)
+            .with_graceful_shutdown(async move { shutdown.wait().await })
+            .await
+    });
+    tracing::info!(%listen, path, "Listening for webhooks");
+
+    set(&syncer, url).await;
+
+    let runtime = tokio::runtime::Handle::current();
+    let daemon = tokio::task::spawn_blocking(move || {
+        runtime.block_on(daemon::run(&syncer, schedule));
+    });
+    if let Err(e) = daemon.await {
+        tracing::error!("Scheduled sync failed: {:?}", e);
+    }
+    match server.await {
+        Ok(Ok(())) => tracing::info!("Webhook server stopped"),
+        Ok(Err(e)) => tracing::error!("Webhook server failed: {:?}", e),
+        Err(e) => tracing::error!("Webhook server task failed: {:?}", e),
+    }
+}
+
+/// Registers `url` as the webhook of every configured token.
+pub async fn set(syncer: &Syncer, url: &str) {
+    for token in config::get_multiple_monobank_tokens() {
+        match api::set_webhook(&syncer.client, &token, url).await {
+            Ok(()) => store(syncer, &token, Some(url)).await,
+            Err(e) => tracing::error!(
+                token = utils::token_fingerprint(&token),
+                "Failed to set webhook: {:?}",
+                e
+            ),
+        }
+    }
+}
+
+/// Asks Monobank which webhook each configured token has and records it.
+pub async fn show(syncer: &Syncer) {
+    for token in config::get_multiple_monobank_tokens() {
+        let client_info = match api::fetch_client_info(&syncer.client, &token).await {
+            Ok(info) => info,
+            Err(e) => {
+                tracing::error!("Failed to fetch client info: {:?}", e);
+                continue;
+            }
+        };
+        let webhook_url = client_info.web_hook_url.filter(|url| !url.is_empty());
+        let stored = models::ClientInfo {
+            client_id: client_info.client_id,
+            name: client_info.name,
+            token: token.clone(),
+        };
+        if let Err(e) = crud::insert_client_info(&syncer.pool, stored.clone()).await {
+            tracing::warn!("Failed to insert client info: {:?}", e);
+        }
+        store(syncer, &token, webhook_url.as_deref()).await;
+        tracing::info!(
+            client = stored.name,
+            token = utils::token_fingerprint(&token),
+            webhook_url = webhook_url.as_deref().unwrap_or("none"),
+            "Webhook",
+        );
+    }
+}
+
+/// Stops Monobank from pushing to any webhook for every configured token.
+pub async fn clear(syncer: &Syncer) {
+    for token in config::get_multiple_monobank_tokens() {
+        match api::set_webhook(&syncer.client, &token, "").await {
+            Ok(()) => store(syncer, &token, None).await,
+            Err(e) => tracing::error!(
+                token = utils::token_fingerprint(&token),
+                "Failed to clear webhook: {:?}",
+                e
+            ),
+        }
+    }
+}
+
+async fn store(syncer: &Syncer, token: &str, webhook_url: Option<&str>) {
+    match crud::update_webhook_url(&syncer.pool, token, webhook_url).await {
+        Ok(0) => {
+            tracing::debug!("Client is not stored yet, its webhook is recorded on the next sync")
+        }
+        Ok(_) => {}
+        Err(e) => tracing::warn!("Failed to update client webhook: {:?}", e),
+    }
+}
+
+/// Monobank checks the URL with a GET request when it is set and expects a 200.
+async fn validate() -> StatusCode {
+    tracing::info!("Webhook URL validated");
+    StatusCode::OK
+}
+
+/// Anything but a 200 makes Monobank retry, so only storage failures return an error.
+async fn receive(State(state): State<WebhookState>, body: String) -> StatusCode {
+    let schema::WebhookEvent::StatementItem(event) =
+        match serde_json::from_str::<schema::WebhookEvent>(&body) {
+            Ok(event) => event,
+            Err(e) => {
+                tracing::warn!("Ignoring unexpected webhook body: {:?}", e);
+                return StatusCode::OK;
+            }
+        };
+    match crud::get_account_token(&state.pool, &event.account).await {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            tracing::debug!(
+                account_id = event.account,
+                "Ignoring webhook for an account that is not synced"
+            );
+            return StatusCode::OK;
+        }
+        Err(e) => {
+            tracing::error!("Failed to look up account {}: {:?}", event.account, e);
+            return StatusCode::INTERNAL_SERVER_ERROR;
+        }
+    }
+    let mut item = sync::statement_item_from(&event.account, event.statement_item);
+    let tags = state
+        .categorizer
+        .read()
+        .expect("Categorizer lock poisoned")
+        .categorize(&mut item);
+    let item_id = item.id.clone();
+    let operation_amount = item.operation_money();
+    if state.sinks.exclusive() {
+        state.sinks.write_statement_items(
+            &event.account,
+            utils::current_timestamp(),
+            std::slice::from_ref(&item),
+        );
+        tracing::info!(
+            account_id = event.account,
+            item_id,
+            amount = %operation_amount,
+            "Passed on pushed statement item"
+        );
+        return StatusCode::OK;
+    }
+    match crud::insert_statement_item(&state.pool, item.clone()).await {
+        Ok(true) => {
+            state.sinks.write_statement_items(
+                &event.account,
+                utils::current_timestamp(),
+                std::slice::from_ref(&item),
+            );
+            if !tags.is_empty() {
+                if let Err(e) = crud::replace_rule_tags(&state.pool, &item_id, &tags).await {
+                    tracing::warn!("Failed to store tags of {}: {:?}", item_id, e);
+                }
+            }
+            tracing::info!(
+                account_id = event.account,
+                item_id,
+                amount = %operation_amount,
+                tags = tags.join(","),
+                "Stored pushed statement item"
+            );
+            StatusCode::OK
+        }
+        Ok(false) => {
+            tracing::debug!(item_id, "Pushed statement item is already stored");
+            StatusCode::OK
+        }
+        Err(e) => {
+            tracing::error!("Failed to insert pushed statement: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
@@ -0,0 +1,318 @@
+//! Runs the queries in `crud` against the database in `TEST_DATABASE_URL`, with whichever
+//! backend the crate is built for. Without it set every test passes without doing anything,
+//! CI sets it for each backend.
+
+use monobank_sync_rust::db_types::DatabasePool;
+use monobank_sync_rust::testing::{self, at};
+use monobank_sync_rust::{crud, db, models};
+use serde_with::chrono::{NaiveDateTime, TimeDelta};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+async fn connect() -> Option<DatabasePool> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("TEST_DATABASE_URL is not set, skipping");
+        return None;
+    };
+    Some(db::initialize(&url).await)
+}
+
+/// A number no earlier run against the same database used.
+fn unique_number() -> u64 {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let micros = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_micros() as u64;
+    micros * 1_000 + COUNTER.fetch_add(1, Ordering::Relaxed)
+}
+
+fn unique(prefix: &str) -> String {
+    format!("{prefix}-{}", unique_number())
+}
+
+async fn insert_account(pool: &DatabasePool) -> models::Account {
+    let client_id = unique("client");
+    crud::insert_client_info(
+        pool,
+        models::ClientInfo {
+            client_id: client_id.clone(),
+            name: "Test".to_string(),
+            token: unique("token"),
+        },
+    )
+    .await
+    .unwrap();
+    let account = models::Account {
+        id: unique("account"),
+        client_id,
+        send_id: "send".to_string(),
+        balance: 10_000,
+        credit_limit: 0,
+        account_type: "black".to_string(),
+        currency_code: 980,
+        cashback_type: None,
+        iban: Some(unique("UA")),
+        last_sync_at: None,
+    };
+    crud::insert_account(pool, account.clone()).await.unwrap();
+    account
+}
+
+fn statement_item(account_id: &str, time: NaiveDateTime, amount: i64) -> models::StatementItem {
+    models::StatementItem {
+        balance: 10_000 + amount,
+        ..testing::statement_item(&unique("item"), account_id, time, amount)
+    }
+}
+
+#[tokio::test]
+async fn accounts_keep_their_sync_cursor() {
+    let Some(pool) = connect().await else { return };
+    let account = insert_account(&pool).await;
+    // Inserting an account again is a no-op
+    crud::insert_account(&pool, account.clone()).await.unwrap();
+
+    assert!(crud::get_account_ids(&pool)
+        .await
+        .unwrap()
+        .contains(&account.id));
+    assert_eq!(
+        crud::get_last_sync_time(&pool, account.id.clone())
+            .await
+            .unwrap(),
+        None
+    );
+    crud::update_last_sync_time(&pool, account.id.clone(), Some(at(5, 12)))
+        .await
+        .unwrap();
+    assert_eq!(
+        crud::get_last_sync_time(&pool, account.id.clone())
+            .await
+            .unwrap(),
+        Some(at(5, 12))
+    );
+    assert!(crud::get_account_token(&pool, &account.id)
+        .await
+        .unwrap()
+        .is_some());
+    assert_eq!(
+        crud::get_account_token(&pool, &unique("missing"))
+            .await
+            .unwrap(),
+        None
+    );
+}
+
+#[tokio::test]
+async fn statement_items_are_stored_once() {
+    let Some(pool) = connect().await else { return };
+    let account = insert_account(&pool).await;
+    let item = statement_item(&account.id, at(2, 10), -4_500);
+
+    assert!(crud::insert_statement_item(&pool, item.clone())
+        .await
+        .unwrap());
+    assert!(!crud::insert_statement_item(&pool, item.clone())
+        .await
+        .unwrap());
+    assert!(crud::statement_item_exists(&pool, &item.id).await.unwrap());
+
+    let stored = crud::get_statement_items(&pool, Some(&account.id), None)
+        .await
+        .unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].id, item.id);
+    assert_eq!(stored[0].time, item.time);
+    assert_eq!(stored[0].amount, -4_500);
+    assert_eq!(stored[0].mcc, 5814);
+    assert_eq!(stored[0].category.as_deref(), Some("cafes"));
+}
+
+#[tokio::test]
+async fn statement_items_of_unknown_accounts_are_errors() {
+    let Some(pool) = connect().await else { return };
+    let item = statement_item(&unique("missing"), at(2, 10), -100);
+
+    assert!(crud::insert_statement_item(&pool, item).await.is_err());
+}
+
+/// MySQL in strict mode refuses values too long for their column, which `INSERT IGNORE`
+/// would have truncated with a warning.
+#[cfg(feature = "mysql")]
+#[tokio::test]
+async fn statement_items_with_too_long_ids_are_errors() {
+    let Some(pool) = connect().await else { return };
+    let account = insert_account(&pool).await;
+    let mut item = statement_item(&account.id, at(2, 10), -100);
+    item.id = "x".repeat(300);
+
+    assert!(crud::insert_statement_item(&pool, item).await.is_err());
+}
+
+#[tokio::test]
+async fn statement_items_are_counted_and_deleted_from_a_time_on() {
+    let Some(pool) = connect().await else { return };
+    let account = insert_account(&pool).await;
+    for day in 1..=4 {
+        let item = statement_item(&account.id, at(day, 9), -100);
+        crud::insert_statement_item(&pool, item).await.unwrap();
+    }
+
+    assert_eq!(
+        crud::count_statement_items_since(&pool, &account.id, at(3, 0))
+            .await
+            .unwrap(),
+        2
+    );
+    let range = Some((at(2, 0), at(3, 23)));
+    assert_eq!(
+        crud::get_statement_items(&pool, Some(&account.id), range)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+    assert_eq!(
+        crud::delete_statement_items_since(&pool, &account.id, at(3, 0))
+            .await
+            .unwrap(),
+        2
+    );
+    assert_eq!(
+        crud::get_statement_items(&pool, Some(&account.id), None)
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn currency_rates_are_stored_once_per_date() {
+    let Some(pool) = connect().await else { return };
+    // Rates are unique per pair and date, so every run takes a date of its own
+    let date = at(1, 0) - TimeDelta::seconds((unique_number() % 1_000_000_000) as i64);
+    let rate = models::CurrencyRate {
+        currency_code_a: 840,
+        currency_code_b: 980,
+        date,
+        rate_buy: Some(41.1),
+        rate_sell: Some(41.6),
+        rate_cross: None,
+        fetched_at: date,
+    };
+
+    assert!(crud::insert_currency_rate(&pool, rate.clone())
+        .await
+        .unwrap());
+    assert!(!crud::insert_currency_rate(&pool, rate).await.unwrap());
+    assert!(crud::get_currency_rates(&pool)
+        .await
+        .unwrap()
+        .iter()
+        .any(|stored| stored.date == date && stored.rate_sell == Some(41.6)));
+
+    crud::insert_currency_rate_fetch(&pool, date, 1, 0)
+        .await
+        .unwrap();
+    let last = crud::get_last_rates_fetch(&pool).await.unwrap();
+    assert!(last.is_some_and(|last| last >= date));
+}
+
+#[tokio::test]
+async fn annotations_and_tags_round_trip() {
+    let Some(pool) = connect().await else { return };
+    let id = unique("item");
+
+    crud::upsert_annotation(&pool, &id, "first", at(1, 8))
+        .await
+        .unwrap();
+    crud::upsert_annotation(&pool, &id, "second", at(1, 9))
+        .await
+        .unwrap();
+    let annotation = crud::get_annotation(&pool, &id).await.unwrap().unwrap();
+    assert_eq!(annotation.note, "second");
+    assert_eq!(annotation.updated_at, at(1, 9));
+    assert!(crud::delete_annotation(&pool, &id).await.unwrap());
+    assert!(crud::get_annotation(&pool, &id).await.unwrap().is_none());
+
+    assert!(crud::insert_transaction_tag(&pool, &id, "trip", at(1, 8))
+        .await
+        .unwrap());
+    assert!(!crud::insert_transaction_tag(&pool, &id, "trip", at(1, 9))
+        .await
+        .unwrap());
+    assert_eq!(
+        crud::get_transaction_tags(&pool, &id).await.unwrap(),
+        vec!["trip".to_string()]
+    );
+    assert!(crud::delete_transaction_tag(&pool, &id, "trip")
+        .await
+        .unwrap());
+    assert!(crud::get_transaction_tags(&pool, &id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test]
+async fn rule_tags_are_replaced() {
+    let Some(pool) = connect().await else { return };
+    let account = insert_account(&pool).await;
+    let item = statement_item(&account.id, at(2, 10), -100);
+    crud::insert_statement_item(&pool, item.clone())
+        .await
+        .unwrap();
+
+    let tags = ["coffee".to_string(), "daily".to_string()];
+    crud::replace_rule_tags(&pool, &item.id, &tags)
+        .await
+        .unwrap();
+    crud::replace_rule_tags(&pool, &item.id, &tags[1..])
+        .await
+        .unwrap();
+    let stored = crud::get_rule_tags(&pool, Some(&account.id)).await.unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].tag, "daily");
+}
+
+#[tokio::test]
+async fn internal_transfers_and_journal_exports_are_recorded_once() {
+    let Some(pool) = connect().await else { return };
+    let account = insert_account(&pool).await;
+    let other = insert_account(&pool).await;
+    let outflow = statement_item(&account.id, at(3, 8), -1_000);
+    let inflow = statement_item(&other.id, at(3, 8), 1_000);
+    for item in [&outflow, &inflow] {
+        crud::insert_statement_item(&pool, item.clone())
+            .await
+            .unwrap();
+    }
+    let (outflow, inflow) = (outflow.id, inflow.id);
+
+    assert!(
+        crud::insert_internal_transfer(&pool, &outflow, &inflow, "counter_iban", at(3, 8))
+            .await
+            .unwrap()
+    );
+    assert!(
+        !crud::insert_internal_transfer(&pool, &outflow, &inflow, "counter_iban", at(3, 9))
+            .await
+            .unwrap()
+    );
+
+    let target = unique("journal");
+    crud::insert_exported_item(&pool, "beancount", &target, &outflow, at(3, 8))
+        .await
+        .unwrap();
+    crud::insert_exported_item(&pool, "beancount", &target, &outflow, at(3, 9))
+        .await
+        .unwrap();
+    assert_eq!(
+        crud::get_exported_item_ids(&pool, "beancount", &target)
+            .await
+            .unwrap(),
+        vec![outflow]
+    );
+}